@@ -1,23 +1,46 @@
 use std::{
-    env, fmt,
+    collections::{HashMap, HashSet},
+    env, fmt, io,
     ops::Deref,
-    path::{Path, PathBuf},
+    path::{Path, PathBuf, MAIN_SEPARATOR_STR},
+    thread,
     time::{Duration, Instant},
 };
 
 use anyhow::Ok;
 use crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use ignore::WalkBuilder;
 use ratatui::{prelude::*, widgets::*};
 use symbols::border;
+use unicode_width::UnicodeWidthStr;
 
+#[cfg(feature = "archives")]
+use crate::archive;
 use crate::{
-    entry::{EntryKind, EntryList, EntryRenderData},
+    bookmarks::Bookmarks,
+    clipboard::{file_uri, Clipboard, SystemClipboard},
+    command_palette::CommandPalette,
+    config::{self, AutoEnterSingle, Config, DirectoryGrouping, HeaderAlignment, HeaderContent},
+    dir_size::{format_size, DirectorySizeCalculation},
+    dir_stack::DirectoryStack,
+    directory_config::DirectoryConfig,
+    entry::{
+        format_permissions, read_unix_metadata, Entry, EntryKind, EntryList, EntryRenderData,
+        UnixMetadata,
+    },
+    favorites::Favorites,
+    hooks::{CommandSpawner, SystemCommandSpawner},
     hotkeys::{HotkeysRegistry, KeyCombo, PREFERRED_KEY_COMBOS_IN_ORDER},
+    metadata_loader::{is_slow_path, MetadataLoader, MetadataReader},
+    navigation_log::NavigationLog,
+    owners::OwnerNames,
+    pins::Pins,
+    terminal,
 };
 
 /// Enum representing whether the system is currently showing a directory listing or paths from the
 /// database.
-#[derive(Debug, Clone, Copy, PartialEq, Default)]
+#[derive(Debug, Clone, Copy, PartialEq, Default, clap::ValueEnum)]
 pub enum ListMode {
     /// The system is currently showing a directory listing.
     #[default]
@@ -27,10 +50,12 @@ pub enum ListMode {
     /// and recently.
     #[allow(dead_code)]
     Frecent,
-    // TODO: Implement this mode
-    // /// The system is currently showing the user's bookmarks.
-    // #[allow(dead_code)]
-    // Bookmark,
+    /// The system is showing the paths that have been piped to it (see `--stdin`), it can't be
+    /// switched to.
+    #[value(skip)]
+    Stdin,
+    /// The system is currently showing the user's bookmarks.
+    Bookmark,
 }
 
 #[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
@@ -39,16 +64,62 @@ pub enum InputMode {
     Search,
 }
 
-#[derive(Debug, Clone, Copy)]
+/// A confirmation that the user has to accept or decline before an action is performed.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Confirmation {
+    /// Entering a directory that has more entries than the configured threshold
+    EnterLargeDirectory { path: PathBuf, entries_count: usize },
+}
+
+impl Confirmation {
+    fn get_prompt(&self) -> String {
+        match self {
+            Confirmation::EnterLargeDirectory {
+                path,
+                entries_count,
+            } => format!(
+                "'{}' has {entries_count} entries, enter anyway?",
+                path.display()
+            ),
+        }
+    }
+}
+
+/// The outcome of running the app, it tells the caller what the user has chosen.
+#[derive(Debug, PartialEq)]
+pub enum RunOutcome {
+    /// The user has exited and wants to change the directory to the given path
+    ChangedTo(PathBuf),
+    /// The user has exited by selecting the given file
+    SelectedFile(PathBuf),
+    /// The user has exited without wanting to change the directory
+    Cancelled,
+    /// The user has exited and wants to change the directory to the given path and start a
+    /// subshell there
+    OpenShellIn(PathBuf),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub enum Action {
     // Traverse the list
     SelectNext,
     SelectPrevious,
     SelectFirst,
     SelectLast,
+    SelectNextColumn,
+    SelectPreviousColumn,
     ChangeDirectoryToSelectedEntry,
     ChangeDirectoryToParent,
+    ChangeDirectoryToNextSibling,
+    ChangeDirectoryToPreviousSibling,
     ChangeDirectoryToEntryWithIndex(usize),
+    GoToIndex(usize),
+    EnterSelectedEntryAndExit,
+    OpenShellInSelectedEntry,
+    ToggleRepositoryRoot,
+    /// Saves the current directory to the numbered slot (1-9)
+    SetFavoriteSlot(usize),
+    ChangeDirectoryToFavoriteSlot(usize),
 
     // Change the list mode
     SwitchToListMode(ListMode),
@@ -58,19 +129,109 @@ pub enum Action {
 
     // Search Actions
     ResetSearchInput,
-    ExitSearchInput,
+    /// Leaves search mode keeping the filtered entries and the selection
+    AcceptSearchInput,
+    /// Leaves search mode clearing the query, the full listing is restored
+    CancelSearchInput,
     SearchInputBackspace,
+    RecallPreviousSearch,
+    RecallNextSearch,
+    ToggleContentSearch,
+    ToggleInvertedSearch,
+    /// Searches for the first line of the clipboard's contents
+    PasteSearchQuery,
+    /// Enters the first directory among the search results and keeps searching inside it
+    DrillIntoFirstDirectory,
+
+    ToggleHiddenFiles,
+    ToggleQuickJumpHints,
+    ToggleGitIgnoredOnly,
+    ToggleSortDirection,
+    /// Cycles between listing the directories first, last and mixed in with the files
+    CycleDirectoryGrouping,
+    TogglePathBasenames,
+    PushCurrentDirectoryToStack,
+    TogglePinnedEntry,
+    ToggleBookmark,
+    ComputeSelectedDirectorySize,
+
+    // Clipboard Actions
+    CopySelectedEntryPath,
+    CopySelectedEntryRelativePath,
+    CopySelectedEntryName,
+    CopySelectedEntryUri,
 
     ToggleHelp,
+    ToggleSessionStats,
+    OpenCommandPalette,
     Exit,
+    ExitWithoutChangingDirectory,
+}
+
+impl Action {
+    /// Returns the description of the action that is shown in the command palette, actions that
+    /// don't make sense to run from there have no description.
+    pub fn get_description(&self) -> Option<&'static str> {
+        let description = match self {
+            Action::SelectNext => "Select the next entry",
+            Action::SelectPrevious => "Select the previous entry",
+            Action::SelectFirst => "Select the first entry",
+            Action::SelectLast => "Select the last entry",
+            Action::SelectNextColumn => "Select the entry in the next column",
+            Action::SelectPreviousColumn => "Select the entry in the previous column",
+            Action::ChangeDirectoryToSelectedEntry => "Enter the selected entry",
+            Action::ChangeDirectoryToParent => "Go to the parent directory",
+            Action::ChangeDirectoryToNextSibling => "Go to the next directory in the parent",
+            Action::ChangeDirectoryToPreviousSibling => {
+                "Go to the previous directory in the parent"
+            }
+            Action::ToggleRepositoryRoot => "Go to the git repository root and back",
+            Action::EnterSelectedEntryAndExit => "Exit with the selected entry",
+            Action::OpenShellInSelectedEntry => "Exit and open a shell in the selected directory",
+            Action::SwitchToListMode(ListMode::Directory) => "Show the directory listing",
+            Action::SwitchToListMode(ListMode::Frecent) => "Show the most accessed paths",
+            Action::SwitchToListMode(ListMode::Bookmark) => "Show the bookmarks",
+            Action::SwitchToInputMode(InputMode::Search) => "Search",
+            Action::ResetSearchInput => "Reset the search",
+            Action::PasteSearchQuery => "Search for the clipboard's contents",
+            Action::ToggleHiddenFiles => "Toggle hidden files",
+            Action::ToggleQuickJumpHints => "Toggle the quick-jump hints",
+            Action::ToggleGitIgnoredOnly => "Toggle only git-ignored entries",
+            Action::ToggleSortDirection => "Toggle the sort direction",
+            Action::CycleDirectoryGrouping => "Cycle directories first/last/mixed",
+            Action::TogglePathBasenames => "Toggle full paths and basenames",
+            Action::PushCurrentDirectoryToStack => "Push the current directory to the stack",
+            Action::TogglePinnedEntry => "Pin the selected entry to the top (or unpin it)",
+            Action::ToggleBookmark => "Bookmark the selected directory (or remove it)",
+            Action::ComputeSelectedDirectorySize => "Compute the size of the selected directory",
+            Action::CopySelectedEntryPath => "Copy the path of the selected entry",
+            Action::CopySelectedEntryRelativePath => "Copy the relative path of the selected entry",
+            Action::CopySelectedEntryName => "Copy the name of the selected entry",
+            Action::CopySelectedEntryUri => "Copy the selected entry as a file:// URI",
+            Action::ToggleHelp => "Toggle the help",
+            Action::ToggleSessionStats => "Toggle the session statistics",
+            Action::Exit => "Exit",
+            Action::ExitWithoutChangingDirectory => "Quit without changing directory",
+            _ => return None,
+        };
+
+        Some(description)
+    }
 }
 
 /// The main application struct, will hold the state of the application.
 #[derive(Debug)]
 pub struct App {
+    /// The configuration of the app
+    config: Config,
+
     /// A boolean used to signal if the app should exit
     should_exit: bool,
 
+    /// The outcome that should be returned when the app exits, if it's not set the current
+    /// directory is returned
+    exit_outcome: Option<RunOutcome>,
+
     /// The current mode of the list
     list_mode: ListMode,
 
@@ -86,24 +247,176 @@ pub struct App {
     /// A boolean used to signal if the help popup should be shown
     show_help: bool,
 
+    /// How many lines the help popup is scrolled down by
+    help_scroll: u16,
+
+    /// A boolean used to signal if the session statistics popup should be shown
+    show_session_stats: bool,
+
+    /// What the user has done during this session, shown in the session statistics popup
+    session_stats: SessionStats,
+
     /// Current input mode
     input_mode: InputMode,
 
     /// The search input
     search_input: SearchInput,
 
+    /// Whether the current query also matches the contents of the files (see `content_search`)
+    search_contents: bool,
+
+    /// Whether the listing shows the entries that don't match the current query instead of the
+    /// ones that do
+    invert_search: bool,
+
+    /// The queries that have been searched for during this session
+    search_history: SearchHistory,
+
     /// The cursor position
     cursor_position: Option<(u16, u16)>,
 
     /// The buffer of user collected keycodes
     collected_key_combos: Vec<KeyCombo>,
 
+    /// The number typed so far in normal mode, a number followed by `Enter` selects the entry on
+    /// that (1-based) row
+    count_prefix: Option<usize>,
+
     /// The last time a key was pressed, this is used to determine when to reset the key sequence
     last_key_press_time: Option<Instant>,
 
     /// The hotkeys registry, used to store system and entry hotkeys as well as register new ones
     /// and assign dynamically shortcuts to entries
     hotkeys_registry: HotkeysRegistry<InputMode, Action>,
+
+    /// The clipboard used by the copy actions
+    clipboard: Box<dyn Clipboard>,
+
+    /// The spawner used to run the `on_cd_command` hook
+    command_spawner: Box<dyn CommandSpawner>,
+
+    /// A short message displayed in the footer (for example after copying a path), it's cleared on
+    /// the next key press
+    status_message: Option<String>,
+
+    /// The title that should be set on the terminal before the next draw (only used when the
+    /// terminal title follows the current directory)
+    pending_terminal_title: Option<String>,
+
+    /// The time at which the search narrowed the listing down to a single directory, it's only
+    /// tracked when `auto_enter_single` is enabled
+    single_match_time: Option<Instant>,
+
+    /// Whether the lists of arbitrary paths (e.g. the most accessed paths) show only the last
+    /// component of the paths instead of the full paths
+    show_path_basenames: bool,
+
+    /// Whether the current directory is actually an archive that's being browsed
+    is_browsing_archive: bool,
+
+    /// A confirmation that is waiting for the user's answer, while it's set all the key events
+    /// go to the confirmation
+    pending_confirmation: Option<Confirmation>,
+
+    /// The time at which the pending confirmation was asked, it's only tracked when the
+    /// confirmations time out (see `Config::confirmation_timeout`)
+    confirmation_time: Option<Instant>,
+
+    /// The time of the last key press, the app is idle since it started until the first one
+    last_input_time: Option<Instant>,
+
+    /// The command palette, while it's open all the key events go to it
+    command_palette: Option<CommandPalette>,
+
+    /// The resolved names of the owners of the entries
+    owner_names: OwnerNames,
+
+    /// Reads the metadata of the entries in the background while a slow directory is listed
+    metadata_loader: Option<MetadataLoader>,
+
+    /// How the metadata is read by the loader, tests replace it to control what's read
+    metadata_reader: MetadataReader,
+
+    /// The size of a directory that is being computed in the background, on demand
+    directory_size: Option<DirectorySizeCalculation>,
+
+    /// The root of the git repository we've jumped to and the directory we've jumped from, so
+    /// that jumping again takes us back
+    repository_root_jump: Option<(PathBuf, PathBuf)>,
+
+    /// The entries that are listed before the rest of their directory
+    pins: Pins,
+
+    /// The directories listed in the bookmarks list mode
+    bookmarks: Bookmarks,
+
+    /// The directories saved to the numbered slots
+    favorites: Favorites,
+
+    /// The directory whose local config (see `directory_config`) is in effect, it's set even when
+    /// the directory has no config so that it's only read once per visit
+    directory_config_path: Option<PathBuf>,
+
+    /// The local config of the current directory, if it has one
+    directory_config: Option<DirectoryConfig>,
+
+    /// The session's sort direction and hidden files setting from before the local config of the
+    /// current directory overrode them, they're restored when leaving the directory
+    overridden_settings: Option<(bool, bool)>,
+
+    /// The log the visited directories are appended to, when it's enabled
+    navigation_log: Option<NavigationLog>,
+
+    /// Whether the directory listing shows only the entries that git ignores (for cleaning up),
+    /// outside of a git repository everything is shown
+    show_only_git_ignored: bool,
+
+    /// The number of rows of the grid, set while the entries are rendered in columns (see
+    /// `Config::grid`) so that the selection can move between the columns
+    grid_rows: Option<usize>,
+}
+
+/// Picks the directory the app starts in: the current directory, or when it can't be determined
+/// (for example because it was deleted) the home directory and then the root. When falling back
+/// a status message explaining why is returned as well.
+fn get_start_directory(
+    current_directory: io::Result<PathBuf>,
+    home_directory: Option<PathBuf>,
+) -> (PathBuf, Option<String>) {
+    match current_directory {
+        Result::Ok(path) => (path, None),
+        Err(err) => {
+            let path = home_directory.unwrap_or_else(|| PathBuf::from(MAIN_SEPARATOR_STR));
+            let message = format!(
+                "The current directory is unavailable ({err}), showing '{}' instead",
+                path.display()
+            );
+
+            (path, Some(message))
+        }
+    }
+}
+
+/// Returns the closest ancestor of the path (including the path itself) that is the root of a git
+/// repository, `.git` can be a file as well (in worktrees and submodules).
+fn find_repository_root(path: &Path) -> Option<&Path> {
+    path.ancestors().find(|x| x.join(".git").exists())
+}
+
+/// Keeps only the entries of the directory that git ignores. The `ignore` walker visits the
+/// entries that aren't ignored (taking the `.gitignore` files of the parents into account as
+/// well), so the ignored ones are the rest.
+fn retain_git_ignored(entry_list: &mut EntryList, directory: &Path) {
+    let not_ignored: HashSet<PathBuf> = WalkBuilder::new(directory)
+        .max_depth(Some(1))
+        .hidden(false)
+        .ignore(false)
+        .build()
+        .flatten()
+        .map(|x| x.into_path())
+        .collect();
+
+    entry_list.items.retain(|x| !not_ignored.contains(&x.path));
 }
 
 /// The search input struct, used to store the search input value and the current index.
@@ -131,6 +444,109 @@ impl SearchInput {
         self.value.pop();
         self.index -= 1;
     }
+
+    pub fn set<T: Into<String>>(&mut self, value: T) {
+        self.value = value.into();
+        self.index = self.value.chars().count();
+    }
+}
+
+/// What listing a directory ended up with, see `App::change_directory`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ChangeResult {
+    /// The number of entries in the listing (the hidden and ignored ones aren't counted)
+    pub items_count: usize,
+
+    /// The number of entries that couldn't be read and were left out
+    pub unreadable_count: usize,
+}
+
+/// A few counters about what the user has done during the session, they're only kept in memory.
+#[derive(Debug)]
+pub struct SessionStats {
+    /// The number of directories that have been listed
+    directories_visited: usize,
+
+    /// The number of times the search has been started
+    searches: usize,
+
+    /// The number of times an entry has been entered through its quick-jump keys
+    quick_jumps: usize,
+
+    /// When the session has started
+    started_at: Instant,
+}
+
+impl Default for SessionStats {
+    fn default() -> Self {
+        Self {
+            directories_visited: 0,
+            searches: 0,
+            quick_jumps: 0,
+            started_at: Instant::now(),
+        }
+    }
+}
+
+impl SessionStats {
+    /// Formats the time spent in the app as of `now`, e.g. `3m 07s`.
+    fn format_elapsed_time(&self, now: Instant) -> String {
+        let seconds = now.saturating_duration_since(self.started_at).as_secs();
+        format!("{}m {:02}s", seconds / 60, seconds % 60)
+    }
+}
+
+/// The history of the search queries that have been committed during the session, it allows the
+/// user to recall previous queries while in search mode.
+#[derive(Debug, Default)]
+pub struct SearchHistory {
+    /// The queries ordered from the oldest to the most recent, without duplicates
+    entries: Vec<String>,
+
+    /// The position of the currently recalled query, `None` when we're not recalling anything
+    position: Option<usize>,
+}
+
+impl SearchHistory {
+    /// Adds a query to the history, if the query is already in the history it's moved to the most
+    /// recent position.
+    pub fn push<T: AsRef<str>>(&mut self, query: T) {
+        let query = query.as_ref();
+        self.position = None;
+
+        if query.is_empty() {
+            return;
+        }
+
+        self.entries.retain(|x| x != query);
+        self.entries.push(query.to_string());
+    }
+
+    /// Moves to the previous (older) query and returns it, we stay on the oldest query once we've
+    /// reached it.
+    pub fn recall_previous(&mut self) -> Option<&str> {
+        let position = match self.position {
+            Some(position) => position.saturating_sub(1),
+            None => self.entries.len().checked_sub(1)?,
+        };
+
+        self.position = Some(position);
+        self.entries.get(position).map(String::as_str)
+    }
+
+    /// Moves to the next (more recent) query and returns it, returns `None` once we've moved past
+    /// the most recent query.
+    pub fn recall_next(&mut self) -> Option<&str> {
+        let position = self.position? + 1;
+
+        if position < self.entries.len() {
+            self.position = Some(position);
+            self.entries.get(position).map(String::as_str)
+        } else {
+            self.position = None;
+            None
+        }
+    }
 }
 
 impl Deref for SearchInput {
@@ -156,18 +572,52 @@ impl fmt::Display for SearchInput {
 impl Default for App {
     fn default() -> Self {
         Self {
+            config: Config::default(),
             should_exit: false,
+            exit_outcome: None,
             list_mode: ListMode::Directory,
             entry_list: EntryList::default(),
             list_state: ListState::default(),
             current_directory: PathBuf::new(),
             show_help: false,
+            help_scroll: 0,
+            show_session_stats: false,
+            session_stats: SessionStats::default(),
             input_mode: InputMode::Normal,
             search_input: SearchInput::default(),
+            search_contents: false,
+            invert_search: false,
+            search_history: SearchHistory::default(),
             cursor_position: None,
             collected_key_combos: Vec::new(),
+            count_prefix: None,
             last_key_press_time: None,
             hotkeys_registry: HotkeysRegistry::new_with_default_system_hotkeys(),
+            clipboard: Box::new(SystemClipboard),
+            command_spawner: Box::new(SystemCommandSpawner),
+            status_message: None,
+            pending_terminal_title: None,
+            single_match_time: None,
+            show_path_basenames: false,
+            is_browsing_archive: false,
+            pending_confirmation: None,
+            confirmation_time: None,
+            command_palette: None,
+            owner_names: OwnerNames::default(),
+            grid_rows: None,
+            repository_root_jump: None,
+            pins: Pins::default(),
+            bookmarks: Bookmarks::default(),
+            favorites: Favorites::default(),
+            show_only_git_ignored: false,
+            directory_config_path: None,
+            directory_config: None,
+            overridden_settings: None,
+            navigation_log: None,
+            last_input_time: None,
+            metadata_loader: None,
+            metadata_reader: read_unix_metadata,
+            directory_size: None,
         }
     }
 }
@@ -176,56 +626,429 @@ impl App {
     /// This timeout is used to determine when a key sequence should be reset due to inactivity.
     const INACTIVITY_TIMEOUT: Duration = Duration::from_millis(500);
 
+    /// How long the search has to stay on a single directory before we act on it, this prevents
+    /// us from jumping into a directory while the user is still typing a longer query.
+    const AUTO_ENTER_DELAY: Duration = Duration::from_millis(400);
+
+    /// How often the countdown of a confirmation that times out is updated
+    const CONFIRMATION_COUNTDOWN_INTERVAL: Duration = Duration::from_millis(250);
+
+    /// How often we check for the metadata that has been read in the background
+    const METADATA_POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+    /// How long computing the size of a directory may take before we settle for a partial size
+    const DIRECTORY_SIZE_TIMEOUT: Duration = Duration::from_secs(30);
+
+    /// How often we check whether the size of a directory has been computed
+    const DIRECTORY_SIZE_POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+    /// What's shown in place of the metadata that hasn't been read yet
+    const LOADING_PLACEHOLDER: &'static str = "…";
+
+    /// The style of the selected entry, both in the list and in the grid
+    const SELECTED_ENTRY_STYLE: Style = Style::new().bg(Color::Gray).fg(Color::Black);
+
+    /// Creates a new instance of the application with the given configuration, without listing any
+    /// directory.
+    pub fn new(config: Config) -> Self {
+        let mut app = App {
+            config,
+            ..Default::default()
+        };
+
+        if let Some(c) = app.config.command_palette_key {
+            app.hotkeys_registry.register_system_hotkey(
+                InputMode::Normal,
+                &[KeyCombo::from(c)],
+                Action::OpenCommandPalette,
+            );
+        }
+
+        // The trie maps any number of sequences to the same action, so the extra keys simply
+        // sit next to the default ones
+        for &c in app.config.enter_keys.iter() {
+            app.hotkeys_registry.register_system_hotkey(
+                InputMode::Normal,
+                &[KeyCombo::from(c)],
+                Action::ChangeDirectoryToSelectedEntry,
+            );
+        }
+
+        if let Some(file_path) = &app.config.pins_file {
+            match Pins::load_from_disk(file_path) {
+                Result::Ok(pins) => app.pins = pins,
+                Err(err) => app.status_message = Some(format!("Failed to load the pins: {err}")),
+            }
+        }
+
+        if let Some(file_path) = &app.config.bookmarks_file {
+            match Bookmarks::load_from_disk(file_path) {
+                Result::Ok(bookmarks) => app.bookmarks = bookmarks,
+                Err(err) => {
+                    app.status_message = Some(format!("Failed to load the bookmarks: {err}"))
+                }
+            }
+        }
+
+        if let Some(file_path) = &app.config.favorites_file {
+            match Favorites::load_from_disk(file_path) {
+                Result::Ok(favorites) => app.favorites = favorites,
+                Err(err) => {
+                    app.status_message = Some(format!("Failed to load the favorites: {err}"))
+                }
+            }
+        }
+
+        if let Some(file_path) = &app.config.navigation_log_file {
+            match NavigationLog::open(file_path) {
+                Result::Ok(log) => app.navigation_log = Some(log),
+                Err(err) => app.status_message = Some(format!("Failed to open the log: {err:#}")),
+            }
+        }
+
+        app
+    }
+
+    /// Creates a new instance of the application that lists the given entries (in the given order)
+    /// as the contents of the current directory, without touching the file system. This is mostly
+    /// useful for exercising the app deterministically in tests.
+    pub fn from_entries(entries: Vec<Entry>, current_directory: PathBuf) -> Self {
+        App {
+            entry_list: EntryList {
+                items: entries,
+                ..Default::default()
+            },
+            current_directory,
+            ..Default::default()
+        }
+    }
+
     /// Tries to create a new instance of the application in a given list mode.
-    pub fn try_new(mode: ListMode) -> anyhow::Result<Self> {
-        let path = env::current_dir()?;
+    pub fn try_new(mode: ListMode, config: Config) -> anyhow::Result<Self> {
+        let (path, status_message) =
+            get_start_directory(env::current_dir(), config::get_home_directory());
 
-        match mode {
+        let mut app = match mode {
             ListMode::Directory => {
-                let mut app = App::default();
+                let mut app = App::new(config);
                 app.change_directory(path)?;
-                Ok(app)
+                app
             }
-            ListMode::Frecent => {
+            ListMode::Frecent | ListMode::Bookmark => {
+                // We keep track of the current directory, so that switching to the directory mode
+                // lists it
                 let mut app = App {
-                    list_mode: ListMode::Frecent,
-                    ..Default::default()
+                    current_directory: path,
+                    ..App::new(config)
                 };
-                app.change_list_mode(ListMode::Frecent)?;
-                Ok(app)
+                app.change_list_mode(mode)?;
+                app
             }
+            // The paths are passed to `try_from_paths`, there's nothing to list without them
+            ListMode::Stdin => App {
+                list_mode: ListMode::Stdin,
+                current_directory: path,
+                ..App::new(config)
+            },
+        };
+
+        app.status_message = status_message;
+
+        if let Some(query) = app.config.default_query.clone() {
+            app.search_input.set(query);
+            app.update_filtered_indices();
         }
+
+        Ok(app)
+    }
+
+    /// Tries to create a new instance of the application that lists the given paths, like the
+    /// most accessed paths they're listed as is (in the given order) and they don't have to exist.
+    pub fn try_from_paths(paths: Vec<PathBuf>, config: Config) -> anyhow::Result<Self> {
+        let (path, status_message) =
+            get_start_directory(env::current_dir(), config::get_home_directory());
+
+        let mut app = App {
+            list_mode: ListMode::Stdin,
+            entry_list: EntryList::from(paths),
+            current_directory: path,
+            status_message,
+            ..App::new(config)
+        };
+        app.entry_list.set_path_names(app.show_path_basenames);
+
+        Ok(app)
     }
 
-    /// Changes the current directory and sorts the entries in the new directory.
-    pub fn change_directory<T: AsRef<Path>>(&mut self, path: T) -> anyhow::Result<()> {
+    /// Changes the current directory and sorts the entries in the new directory. The entries that
+    /// can't be read are left out, how many of them there were is reported in the result (and in
+    /// the footer).
+    pub fn change_directory<T: AsRef<Path>>(&mut self, path: T) -> anyhow::Result<ChangeResult> {
         let entries = std::fs::read_dir(path.as_ref())?;
-        let mut entry_list = EntryList::try_from(entries)?;
+        let is_slow = is_slow_path(path.as_ref(), &self.config.slow_path_prefixes);
+        let mut entry_list = EntryList::from_read_dir(entries, !is_slow)?;
+        let unreadable_count = entry_list.unreadable_count;
 
-        entry_list.items.sort_by(|a, b| {
-            match (&a.kind, &b.kind) {
-                (EntryKind::Directory, EntryKind::Directory)
-                | (EntryKind::File { .. }, EntryKind::File { .. }) => a
-                    .name
-                    .to_lowercase()
-                    .partial_cmp(&b.name.to_lowercase())
-                    .unwrap(),
-                // Otherwise, put folders first
-                (EntryKind::Directory, EntryKind::File { .. }) => std::cmp::Ordering::Less,
-                (EntryKind::File { .. }, EntryKind::Directory) => std::cmp::Ordering::Greater,
+        self.apply_directory_config(path.as_ref());
+
+        if let Some(directory_config) = &self.directory_config {
+            entry_list
+                .items
+                .retain(|x| !directory_config.ignore.contains(&x.name));
+        }
+
+        if self.show_only_git_ignored && find_repository_root(path.as_ref()).is_some() {
+            retain_git_ignored(&mut entry_list, path.as_ref());
+        }
+
+        self.show_entry_list(entry_list, path);
+        self.session_stats.directories_visited += 1;
+
+        if is_slow {
+            let paths = self
+                .entry_list
+                .items
+                .iter()
+                .map(|x| x.path.clone())
+                .collect();
+            self.metadata_loader = Some(MetadataLoader::spawn(paths, self.metadata_reader));
+        }
+
+        self.run_on_cd_command();
+        self.record_navigation();
+
+        let result = ChangeResult {
+            items_count: self.entry_list.items.len(),
+            unreadable_count,
+        };
+
+        if result.unreadable_count > 0 {
+            self.status_message = Some(format!(
+                "{} items, {} unreadable",
+                result.items_count, result.unreadable_count
+            ));
+        }
+
+        Ok(result)
+    }
+
+    /// Reads the local config of the directory when entering it and overlays it onto the session's
+    /// config, the overridden settings of the directory we've left are restored first.
+    fn apply_directory_config(&mut self, directory: &Path) {
+        // Listing the same directory again (e.g. after toggling a setting) keeps what's in effect
+        if self.directory_config_path.as_deref() == Some(directory) {
+            return;
+        }
+
+        self.directory_config_path = Some(directory.to_path_buf());
+
+        if let Some((sort_descending, hide_hidden_files)) = self.overridden_settings.take() {
+            self.config.sort_descending = sort_descending;
+            self.config.hide_hidden_files = hide_hidden_files;
+        }
+
+        self.directory_config = match DirectoryConfig::load(directory) {
+            Result::Ok(x) => x,
+            Err(err) => {
+                self.status_message = Some(format!("Ignoring the directory's config: {err:#}"));
+                None
             }
-        });
+        };
+
+        let Some(directory_config) = &self.directory_config else {
+            return;
+        };
+
+        self.overridden_settings =
+            Some((self.config.sort_descending, self.config.hide_hidden_files));
+
+        if let Some(sort_descending) = directory_config.sort_descending {
+            self.config.sort_descending = sort_descending;
+        }
+
+        if let Some(hide_hidden) = directory_config.hide_hidden {
+            self.config.hide_hidden_files = hide_hidden;
+        }
+    }
+
+    /// Lists the top-level members of the archive as if it was a directory, the listing is
+    /// read-only so its members can't be entered.
+    #[cfg(feature = "archives")]
+    pub fn change_directory_to_archive<T: AsRef<Path>>(&mut self, path: T) -> anyhow::Result<()> {
+        let entry_list = archive::list_zip_entries(path.as_ref())?;
+
+        self.show_entry_list(entry_list, path);
+        self.is_browsing_archive = true;
+
+        Ok(())
+    }
+
+    /// Lists the current directory (or archive) again, for example after a listing option has
+    /// changed.
+    fn reload_current_directory(&mut self) -> anyhow::Result<()> {
+        #[cfg(feature = "archives")]
+        if self.is_browsing_archive {
+            return self.change_directory_to_archive(self.current_directory.clone());
+        }
+
+        self.change_directory(self.current_directory.clone())?;
+
+        Ok(())
+    }
+
+    /// Returns the directory the app exits to, when browsing an archive that's the directory
+    /// containing the archive since the shell can't change into it.
+    fn get_exit_directory(&self) -> &Path {
+        if self.is_browsing_archive {
+            self.current_directory
+                .parent()
+                .unwrap_or(&self.current_directory)
+        } else {
+            &self.current_directory
+        }
+    }
+
+    /// Filters and sorts the entries and shows them as the contents of the given path.
+    fn show_entry_list<T: AsRef<Path>>(&mut self, mut entry_list: EntryList, path: T) {
+        self.filter_and_sort(&mut entry_list);
 
         self.list_state = ListState::default();
         self.should_exit = false;
+        self.exit_outcome = None;
         self.list_mode = ListMode::Directory;
         self.entry_list = entry_list;
         self.current_directory = path.as_ref().to_path_buf();
-        self.search_input.clear();
+        self.is_browsing_archive = false;
+        self.metadata_loader = None;
+
+        if self.config.persist_search_on_cd {
+            self.update_filtered_indices();
+        } else {
+            self.search_input.clear();
+            self.search_contents = false;
+            self.invert_search = false;
+        }
+
+        if self.config.set_terminal_title {
+            self.pending_terminal_title = Some(self.current_directory.display().to_string());
+        }
+    }
+
+    /// Hides the entries that shouldn't be shown and sorts the rest the way they're listed.
+    fn filter_and_sort(&self, entry_list: &mut EntryList) {
+        if self.config.hide_hidden_files {
+            entry_list
+                .items
+                .retain(|x| !x.is_hidden() || self.config.always_show.contains(&x.name));
+        }
+
+        // Pinned entries and the entries that are always shown (when asked to) go to the top,
+        // `false` sorts before `true`
+        let is_not_pinned = |entry: &Entry| {
+            !(self.pins.is_pinned(&entry.path)
+                || self.config.always_show_on_top && self.config.always_show.contains(&entry.name))
+        };
+
+        entry_list.items.sort_by(|a, b| {
+            is_not_pinned(a)
+                .cmp(&is_not_pinned(b))
+                .then_with(|| {
+                    let directories_first = match (&a.kind, &b.kind) {
+                        (EntryKind::Directory, EntryKind::File { .. }) => std::cmp::Ordering::Less,
+                        (EntryKind::File { .. }, EntryKind::Directory) => {
+                            std::cmp::Ordering::Greater
+                        }
+                        _ => std::cmp::Ordering::Equal,
+                    };
+
+                    match self.config.directory_grouping {
+                        DirectoryGrouping::First => directories_first,
+                        DirectoryGrouping::Last => directories_first.reverse(),
+                        DirectoryGrouping::Mixed => std::cmp::Ordering::Equal,
+                    }
+                })
+                .then_with(|| {
+                    let ordering = a.name_lower.cmp(&b.name_lower);
+
+                    if self.config.sort_descending {
+                        ordering.reverse()
+                    } else {
+                        ordering
+                    }
+                })
+        });
+    }
+
+    /// Goes to the next (or the previous) directory next to the current one, in the order they're
+    /// listed in their parent. At the ends we either wrap around or stay (see
+    /// `Config::wrap_siblings`).
+    fn change_directory_to_sibling(&mut self, next: bool) -> anyhow::Result<()> {
+        let Some(parent) = self.current_directory.parent() else {
+            return Ok(());
+        };
+
+        // The metadata isn't needed to tell the directories apart
+        let mut entry_list = EntryList::from_read_dir(std::fs::read_dir(parent)?, false)?;
+        self.filter_and_sort(&mut entry_list);
+
+        let siblings = entry_list
+            .items
+            .iter()
+            .filter(|x| x.kind == EntryKind::Directory)
+            .map(|x| &x.path)
+            .collect::<Vec<_>>();
+
+        let Some(position) = siblings.iter().position(|x| **x == self.current_directory) else {
+            return Ok(());
+        };
+
+        let sibling = if next {
+            match siblings.get(position + 1) {
+                Some(sibling) => Some(sibling),
+                None if self.config.wrap_siblings => siblings.first(),
+                None => None,
+            }
+        } else {
+            match position.checked_sub(1) {
+                Some(index) => siblings.get(index),
+                None if self.config.wrap_siblings => siblings.last(),
+                None => None,
+            }
+        };
+
+        match sibling {
+            Some(&sibling) if *sibling != self.current_directory => {
+                let sibling = sibling.clone();
+                self.change_directory(sibling)?;
+            }
+            _ => self.status_message = Some("There's no other directory in this direction".into()),
+        }
 
         Ok(())
     }
 
+    fn run_on_cd_command(&mut self) {
+        let Some(command) = &self.config.on_cd_command else {
+            return;
+        };
+
+        if let Err(err) = self.command_spawner.spawn(command, &self.current_directory) {
+            self.status_message = Some(format!("Failed to run the on cd command: {err:#}"));
+        }
+    }
+
+    fn record_navigation(&mut self) {
+        let Some(navigation_log) = &mut self.navigation_log else {
+            return;
+        };
+
+        // We stop logging after a failure, rather than complaining about every directory
+        if let Err(err) = navigation_log.record(&self.current_directory) {
+            self.navigation_log = None;
+            self.status_message = Some(format!("Failed to write to the log: {err}"));
+        }
+    }
+
     fn change_list_mode(&mut self, mode: ListMode) -> anyhow::Result<()> {
         if self.list_mode == mode {
             return Ok(());
@@ -234,23 +1057,74 @@ impl App {
         self.list_mode = mode;
 
         match self.list_mode {
-            ListMode::Directory => self.change_directory(self.current_directory.clone()),
+            ListMode::Directory => self.reload_current_directory(),
             ListMode::Frecent => {
                 // TODO: Fetch the most frecent paths from the database
-                self.entry_list = EntryList::default();
+                self.entry_list = EntryList::from(Vec::new());
+                self.entry_list.set_path_names(self.show_path_basenames);
+                Ok(())
+            }
+            // The piped paths are only listed when the app starts (see `try_from_paths`)
+            ListMode::Stdin => Ok(()),
+            ListMode::Bookmark => {
+                self.list_bookmarks();
                 Ok(())
             }
         }
     }
 
+    /// Lists the bookmarked directories, the ones that don't exist anymore are left out (they
+    /// can't be entered).
+    fn list_bookmarks(&mut self) {
+        let paths: Vec<PathBuf> = self
+            .bookmarks
+            .get_paths()
+            .iter()
+            .filter(|x| x.is_dir())
+            .cloned()
+            .collect();
+
+        self.entry_list = EntryList::from(paths);
+        self.entry_list.set_path_names(self.show_path_basenames);
+        self.update_filtered_indices();
+    }
+
     /// Runs the application's main loop until the user quits.
-    pub fn run<B: Backend>(&mut self, terminal: &mut Terminal<B>) -> anyhow::Result<PathBuf> {
+    pub fn run<B: Backend>(&mut self, terminal: &mut Terminal<B>) -> anyhow::Result<RunOutcome> {
         while !self.should_exit {
+            if let Some(title) = self.pending_terminal_title.take() {
+                terminal::set_title(&mut io::stderr(), &title)?;
+            }
+
             terminal.draw(|frame| self.draw(frame))?;
             self.handle_events()?;
         }
 
-        Ok(self.current_directory.clone())
+        // Quitting the picker over the piped paths means that nothing has been picked
+        let outcome = self
+            .exit_outcome
+            .take()
+            .unwrap_or_else(|| match self.list_mode {
+                ListMode::Stdin => RunOutcome::Cancelled,
+                _ => RunOutcome::ChangedTo(self.get_exit_directory().to_path_buf()),
+            });
+
+        if let Some(duration) = self.config.exit_confirmation_duration {
+            self.status_message = Some(Self::get_exit_confirmation_message(&outcome));
+            terminal.draw(|frame| self.draw(frame))?;
+            thread::sleep(duration);
+        }
+
+        Ok(outcome)
+    }
+
+    fn get_exit_confirmation_message(outcome: &RunOutcome) -> String {
+        match outcome {
+            RunOutcome::ChangedTo(path) => format!("Exiting to '{}'", path.display()),
+            RunOutcome::SelectedFile(path) => format!("Exiting with '{}'", path.display()),
+            RunOutcome::Cancelled => String::from("Exiting without changing the directory"),
+            RunOutcome::OpenShellIn(path) => format!("Opening a shell in '{}'", path.display()),
+        }
     }
 
     fn draw(&mut self, frame: &mut Frame) {
@@ -262,26 +1136,18 @@ impl App {
         }
     }
 
-    fn render_help_popup(&self, buf: &mut Buffer) {
-        let size = buf.area();
-
-        // Define the popup area (e.g., centered and smaller than full screen)
-        let popup_area = Rect {
-            x: size.width / 4,
-            y: size.height / 4,
-            width: size.width / 2,
-            height: size.height / 2,
-        };
-
-        let block = Block::default()
-            .title(" Help ")
-            .title_style(Style::default().bold().fg(Color::Red))
-            .borders(Borders::ALL)
-            .border_type(BorderType::Plain);
-
-        let help_paragraph = Paragraph::new(Text::from(vec![
+    fn get_help_lines() -> Vec<Line<'static>> {
+        vec![
             Line::from("Key Bindings:"),
             Line::from(""),
+            Line::from(vec![
+                Span::styled("> ?", Style::default().fg(Color::Yellow)),
+                Span::raw(" - Toggle help"),
+            ]),
+            Line::from(vec![
+                Span::styled("> q or Esc", Style::default().fg(Color::Yellow)),
+                Span::raw(" - Quit"),
+            ]),
             Line::from(vec![
                 Span::styled("> j/k or ↓/↑", Style::default().fg(Color::Yellow)),
                 Span::raw(" - Move down/up"),
@@ -291,49 +1157,372 @@ impl App {
                 Span::raw(" - Go to top/bottom"),
             ]),
             Line::from(vec![
-                Span::styled("> Ctrl + d/r", Style::default().fg(Color::Yellow)),
-                Span::raw(" - Switch category (d)irectory or (f)recent"),
+                Span::styled("> Ctrl + d/f/b", Style::default().fg(Color::Yellow)),
+                Span::raw(" - Switch category (d)irectory, (f)recent or (b)ookmarks"),
             ]),
             Line::from(vec![
                 Span::styled("> Enter, l or →", Style::default().fg(Color::Yellow)),
                 Span::raw(" - Go into directory"),
             ]),
+            Line::from(vec![
+                Span::styled("> {number} Enter", Style::default().fg(Color::Yellow)),
+                Span::raw(" - Go to row {number}"),
+            ]),
+            Line::from(vec![
+                Span::styled("> L", Style::default().fg(Color::Yellow)),
+                Span::raw(" - Go into directory and quit"),
+            ]),
             Line::from(vec![
                 Span::styled("> h or ←", Style::default().fg(Color::Yellow)),
                 Span::raw(" - Go up a directory"),
             ]),
             Line::from(vec![
-                Span::styled("> ?", Style::default().fg(Color::Yellow)),
-                Span::raw(" - Toggle help"),
+                Span::styled("> R", Style::default().fg(Color::Yellow)),
+                Span::raw(" - Go to the git repository root and back"),
             ]),
             Line::from(vec![
-                Span::styled("> q or Esc", Style::default().fg(Color::Yellow)),
-                Span::raw(" - Quit"),
+                Span::styled("> {/}", Style::default().fg(Color::Yellow)),
+                Span::raw(" - Previous/next directory in the parent"),
+            ]),
+            Line::from(vec![
+                Span::styled("> Ctrl + q", Style::default().fg(Color::Yellow)),
+                Span::raw(" - Quit without changing directory"),
+            ]),
+            Line::from(vec![
+                Span::styled("> T", Style::default().fg(Color::Yellow)),
+                Span::raw(" - Quit and open a shell in the directory"),
             ]),
             Line::from(vec![
                 Span::styled("> /", Style::default().fg(Color::Yellow)),
                 Span::raw(" - Search"),
             ]),
+            Line::from(vec![
+                Span::styled("> :", Style::default().fg(Color::Yellow)),
+                Span::raw(" - Command palette"),
+            ]),
+            Line::from(vec![
+                Span::styled("> Ctrl + p/n", Style::default().fg(Color::Yellow)),
+                Span::raw(" - Previous/next search (while searching)"),
+            ]),
+            Line::from(vec![
+                Span::styled("> Ctrl + g", Style::default().fg(Color::Yellow)),
+                Span::raw(" - Also search file contents (while searching)"),
+            ]),
+            Line::from(vec![
+                Span::styled("> Ctrl + x", Style::default().fg(Color::Yellow)),
+                Span::raw(" - Show what doesn't match instead (while searching)"),
+            ]),
+            Line::from(vec![
+                Span::styled("> Tab", Style::default().fg(Color::Yellow)),
+                Span::raw(" - Enter the first directory and search in it (while searching)"),
+            ]),
+            Line::from(vec![
+                Span::styled("> Ctrl + v", Style::default().fg(Color::Yellow)),
+                Span::raw(" - Search for the clipboard's first line"),
+            ]),
+            Line::from(vec![
+                Span::styled("> Esc/Ctrl + c", Style::default().fg(Color::Yellow)),
+                Span::raw(" - Keep/clear the filtered entries (while searching)"),
+            ]),
             Line::from(vec![
                 Span::styled("> _", Style::default().fg(Color::Yellow)),
                 Span::raw(" - Reset search"),
             ]),
+            Line::from(vec![
+                Span::styled("> .", Style::default().fg(Color::Yellow)),
+                Span::raw(" - Toggle hidden files"),
+            ]),
+            Line::from(vec![
+                Span::styled("> H", Style::default().fg(Color::Yellow)),
+                Span::raw(" - Toggle quick-jump hints"),
+            ]),
+            Line::from(vec![
+                Span::styled("> I", Style::default().fg(Color::Yellow)),
+                Span::raw(" - Show only the git-ignored entries (or all)"),
+            ]),
+            Line::from(vec![
+                Span::styled("> P", Style::default().fg(Color::Yellow)),
+                Span::raw(" - Toggle full paths/names (frecent)"),
+            ]),
+            Line::from(vec![
+                Span::styled("> O", Style::default().fg(Color::Yellow)),
+                Span::raw(" - Toggle sort direction"),
+            ]),
+            Line::from(vec![
+                Span::styled("> F", Style::default().fg(Color::Yellow)),
+                Span::raw(" - Cycle directories first/last/mixed"),
+            ]),
+            Line::from(vec![
+                Span::styled("> M", Style::default().fg(Color::Yellow)),
+                Span::raw(" - Push directory onto the stack (see `popd`)"),
+            ]),
+            Line::from(vec![
+                Span::styled("> *", Style::default().fg(Color::Yellow)),
+                Span::raw(" - Pin/unpin the selected entry"),
+            ]),
+            Line::from(vec![
+                Span::styled("> B", Style::default().fg(Color::Yellow)),
+                Span::raw(" - Bookmark/unbookmark the selected directory"),
+            ]),
+            Line::from(vec![
+                Span::styled("> Ctrl + s 1-9", Style::default().fg(Color::Yellow)),
+                Span::raw(" - Save the directory to a numbered slot"),
+            ]),
+            Line::from(vec![
+                Span::styled("> ' 1-9", Style::default().fg(Color::Yellow)),
+                Span::raw(" - Go to the directory in a numbered slot"),
+            ]),
+            Line::from(vec![
+                Span::styled("> D", Style::default().fg(Color::Yellow)),
+                Span::raw(" - Compute the size of the selected directory"),
+            ]),
+            Line::from(vec![
+                Span::styled("> Y", Style::default().fg(Color::Yellow)),
+                Span::raw(" - Copy path"),
+            ]),
+            Line::from(vec![
+                Span::styled("> Ctrl + y", Style::default().fg(Color::Yellow)),
+                Span::raw(" - Copy relative path"),
+            ]),
+            Line::from(vec![
+                Span::styled("> N", Style::default().fg(Color::Yellow)),
+                Span::raw(" - Copy name"),
+            ]),
+            Line::from(vec![
+                Span::styled("> U", Style::default().fg(Color::Yellow)),
+                Span::raw(" - Copy as file:// URI"),
+            ]),
+            Line::from(vec![
+                Span::styled("> Tab/Shift + Tab", Style::default().fg(Color::Yellow)),
+                Span::raw(" - Next/previous column (with --grid)"),
+            ]),
+            Line::from(vec![
+                Span::styled("> S", Style::default().fg(Color::Yellow)),
+                Span::raw(" - Toggle session statistics"),
+            ]),
+        ]
+    }
+
+    fn render_help_popup(&mut self, buf: &mut Buffer) {
+        let size = buf.area();
+
+        // Define the popup area (e.g., centered and smaller than full screen)
+        let popup_area = Rect {
+            x: size.width / 4,
+            y: size.height / 4,
+            width: size.width / 2,
+            height: size.height / 2,
+        };
+
+        let block = Block::default()
+            .title(" Help ")
+            .title_style(Style::default().bold().fg(Color::Red))
+            .title_bottom(Line::from(" j/k to scroll ").right_aligned())
+            .borders(Borders::ALL)
+            .border_type(BorderType::Plain);
+
+        let help_paragraph = Paragraph::new(Text::from(App::get_help_lines()))
+            .reset()
+            .block(block)
+            .wrap(Wrap { trim: true })
+            .alignment(Alignment::Left);
+
+        // The help doesn't fit on smaller terminals, so it's scrolled with j/k while it's open. We
+        // stop scrolling once the last line is at the bottom of the popup (the line count includes
+        // the borders, but the wrapping happens within them).
+        let lines_count = help_paragraph.line_count(popup_area.width.saturating_sub(2));
+        let max_scroll = lines_count.saturating_sub(popup_area.height as usize);
+        self.help_scroll = self.help_scroll.min(max_scroll as u16);
+
+        let help_paragraph = help_paragraph.scroll((self.help_scroll, 0));
+
+        // Render the help popup in the buffer
+        help_paragraph.render(popup_area, buf);
+    }
+
+    fn render_session_stats_popup(&self, now: Instant, buf: &mut Buffer) {
+        let size = buf.area();
+
+        let popup_area = Rect {
+            x: size.width / 4,
+            y: size.height.saturating_sub(6) / 2,
+            width: size.width / 2,
+            height: 6.min(size.height),
+        };
+
+        let block = Block::default()
+            .title(" Session ")
+            .title_style(Style::default().bold().fg(Color::Red))
+            .borders(Borders::ALL)
+            .border_type(BorderType::Plain);
+
+        let stats = &self.session_stats;
+        let lines = [
+            ("Directories visited", stats.directories_visited.to_string()),
+            ("Searches", stats.searches.to_string()),
+            ("Quick-jumps", stats.quick_jumps.to_string()),
+            ("Time in the app", stats.format_elapsed_time(now)),
+        ]
+        .into_iter()
+        .map(|(label, value)| {
+            Line::from(vec![
+                Span::raw(format!("{label}: ")),
+                Span::styled(value, Style::default().fg(Color::Yellow)),
+            ])
+        })
+        .collect::<Vec<_>>();
+
+        Paragraph::new(Text::from(lines))
+            .reset()
+            .block(block)
+            .render(popup_area, buf);
+    }
+
+    fn ask_for_confirmation(&mut self, confirmation: Confirmation) {
+        self.pending_confirmation = Some(confirmation);
+        self.confirmation_time = self.config.confirmation_timeout.map(|_| Instant::now());
+    }
+
+    /// Returns how long the pending confirmation keeps waiting for an answer as of `now`, `None`
+    /// when it doesn't time out.
+    fn get_confirmation_time_left(&self, now: Instant) -> Option<Duration> {
+        let timeout = self.config.confirmation_timeout?;
+        let t = self.confirmation_time?;
+
+        Some(timeout.saturating_sub(now.saturating_duration_since(t)))
+    }
+
+    /// Cancels the pending confirmation once it has waited for an answer for too long, so that an
+    /// unattended terminal doesn't wait forever.
+    fn cancel_expired_confirmation(&mut self, now: Instant) {
+        if self.get_confirmation_time_left(now) == Some(Duration::ZERO) {
+            self.pending_confirmation = None;
+            self.confirmation_time = None;
+            self.status_message = Some("The confirmation has timed out".into());
+        }
+    }
+
+    /// Returns how long the app keeps waiting for a key press as of `now` before it exits, `None`
+    /// when it doesn't exit by itself.
+    fn get_idle_time_left(&self, now: Instant) -> Option<Duration> {
+        let timeout = self.config.idle_timeout?;
+        let t = self
+            .last_input_time
+            .unwrap_or(self.session_stats.started_at);
+
+        Some(timeout.saturating_sub(now.saturating_duration_since(t)))
+    }
+
+    /// Exits the app once it has been idle for too long (see `Config::idle_timeout`), the outcome
+    /// is the same as quitting.
+    fn exit_if_idle(&mut self, now: Instant) {
+        if self.get_idle_time_left(now) == Some(Duration::ZERO) {
+            self.should_exit = true;
+        }
+    }
+
+    fn render_confirmation_popup(
+        confirmation: &Confirmation,
+        time_left: Option<Duration>,
+        buf: &mut Buffer,
+    ) {
+        let size = buf.area();
+
+        let popup_area = Rect {
+            x: size.width / 6,
+            y: size.height.saturating_sub(5) / 2,
+            width: size.width * 2 / 3,
+            height: 5.min(size.height),
+        };
+
+        let block = Block::default()
+            .title(" Confirm ")
+            .title_style(Style::default().bold().fg(Color::Yellow))
+            .borders(Borders::ALL)
+            .border_type(BorderType::Plain);
+
+        let mut answers = vec![
+            Span::styled("(y)es", Style::default().fg(Color::Green)),
+            Span::raw(" / "),
+            Span::styled("(n)o", Style::default().fg(Color::Red)),
+        ];
+
+        if let Some(time_left) = time_left {
+            // We round up, so that we show 1 second until the very end
+            let seconds = time_left.as_millis().div_ceil(1000);
+            answers.push(Span::styled(
+                format!(" (cancelling in {seconds}s)"),
+                Style::default().dark_gray(),
+            ));
+        }
+
+        Paragraph::new(Text::from(vec![
+            Line::from(confirmation.get_prompt()),
+            Line::from(answers),
         ]))
         .reset()
         .block(block)
         .wrap(Wrap { trim: true })
-        .alignment(Alignment::Left);
-
-        // Render the help popup in the buffer
-        help_paragraph.render(popup_area, buf);
+        .render(popup_area, buf);
     }
 
     /// Updates the application's state based on the user input.
     fn handle_events(&mut self) -> anyhow::Result<()> {
+        self.receive_metadata();
+        self.receive_directory_size();
+
+        // While a single match is pending we only wait for the remainder of the delay, so that we
+        // can act on it if the user stops typing. While metadata is read in the background we
+        // wake up regularly to show what has been read so far, the same goes for the countdown of
+        // a confirmation that times out and for the size of a directory. When idling exits the app
+        // we wake up once the app has been idle for long enough.
+        let now = Instant::now();
+        let single_match_timeout = self
+            .single_match_time
+            .map(|t| Self::AUTO_ENTER_DELAY.saturating_sub(now.saturating_duration_since(t)));
+        let metadata_timeout = self
+            .metadata_loader
+            .as_ref()
+            .map(|_| Self::METADATA_POLL_INTERVAL);
+        let confirmation_timeout = self
+            .get_confirmation_time_left(now)
+            .map(|x| x.min(Self::CONFIRMATION_COUNTDOWN_INTERVAL));
+        let directory_size_timeout = self
+            .directory_size
+            .as_ref()
+            .map(|_| Self::DIRECTORY_SIZE_POLL_INTERVAL);
+
+        let idle_timeout = self.get_idle_time_left(now);
+
+        let timeout = [
+            single_match_timeout,
+            metadata_timeout,
+            confirmation_timeout,
+            directory_size_timeout,
+            idle_timeout,
+        ]
+        .into_iter()
+        .flatten()
+        .min();
+
+        if let Some(timeout) = timeout {
+            if !event::poll(timeout)? {
+                self.cancel_expired_confirmation(Instant::now());
+                self.exit_if_idle(Instant::now());
+
+                if single_match_timeout.is_some_and(|x| x <= timeout) {
+                    return self.auto_enter_single_match(Instant::now());
+                }
+
+                return Ok(());
+            }
+        }
+
         match event::read()? {
             // It's important to check that the event is a key press event as crossterm also emits
             // key release and repeat events on Windows
             Event::Key(key_event) if key_event.kind == KeyEventKind::Press => {
+                self.last_input_time = Some(Instant::now());
                 self.handle_key_event(key_event, key_event.modifiers)?
             }
             // Ignore the rest
@@ -343,15 +1532,123 @@ impl App {
         Ok(())
     }
 
+    /// Fills in the metadata that has been read in the background since the last call
+    fn receive_metadata(&mut self) {
+        let Some(metadata_loader) = &mut self.metadata_loader else {
+            return;
+        };
+
+        let mut received: HashMap<PathBuf, Option<UnixMetadata>> =
+            metadata_loader.try_receive().into_iter().collect();
+
+        if metadata_loader.is_done() {
+            self.metadata_loader = None;
+        }
+
+        if received.is_empty() {
+            return;
+        }
+
+        for entry in self.entry_list.items.iter_mut() {
+            if let Some(metadata) = received.remove(&entry.path) {
+                entry.set_unix_metadata(metadata);
+            }
+        }
+    }
+
+    /// Starts computing the size of the selected directory, or cancels the calculation that's
+    /// already running.
+    fn toggle_selected_directory_size(&mut self) {
+        if let Some(calculation) = self.directory_size.take() {
+            self.status_message = Some(format!(
+                "Stopped computing the size of '{}'",
+                calculation.path.display()
+            ));
+            return;
+        }
+
+        let Some(entry) = self.get_selected_entry() else {
+            return;
+        };
+
+        if entry.kind != EntryKind::Directory || self.is_browsing_archive {
+            self.status_message = Some("Only the size of a directory can be computed".into());
+            return;
+        }
+
+        self.directory_size = Some(DirectorySizeCalculation::spawn(
+            entry.path.clone(),
+            Self::DIRECTORY_SIZE_TIMEOUT,
+        ));
+    }
+
+    /// Shows the size of the directory once it has been computed in the background
+    fn receive_directory_size(&mut self) {
+        let Some(size) = self.directory_size.as_ref().and_then(|x| x.try_receive()) else {
+            return;
+        };
+
+        let Some(calculation) = self.directory_size.take() else {
+            return;
+        };
+
+        let mut message = format!(
+            "'{}' takes {}{} in {} files",
+            calculation.path.display(),
+            if size.is_partial { "at least " } else { "" },
+            format_size(size.bytes),
+            size.files
+        );
+
+        if size.skipped > 0 {
+            message.push_str(&format!(", {} unreadable entries skipped", size.skipped));
+        }
+
+        self.status_message = Some(message);
+    }
+
     fn change_directory_to_entry_index(&mut self, index: usize) -> anyhow::Result<()> {
         let entries = self.entry_list.get_filtered_entries();
         let selected_entry = entries.get(index);
 
         if let Some(selected_entry) = selected_entry {
+            if self.is_browsing_archive {
+                self.status_message = Some("Archive members can't be opened".into());
+                return Ok(());
+            }
+
+            #[cfg(feature = "archives")]
+            if archive::is_supported_archive(&selected_entry.path) {
+                let path = selected_entry.path.clone();
+                return self.change_directory_to_archive(path);
+            }
+
             if selected_entry.kind == EntryKind::Directory {
-                self.change_directory(selected_entry.path.clone())?;
+                let path = selected_entry.path.clone();
+
+                if let Some(threshold) = self.config.large_directory_threshold {
+                    // Counting the entries is cheap compared to listing them, since we don't need
+                    // to look at their metadata
+                    let entries_count = std::fs::read_dir(&path)?.count();
+
+                    if entries_count > threshold {
+                        self.ask_for_confirmation(Confirmation::EnterLargeDirectory {
+                            path,
+                            entries_count,
+                        });
+                        return Ok(());
+                    }
+                }
+
+                self.change_directory(path)?;
             } else {
-                // The user has selected a file, exit
+                // The user has selected a file, exit. When picking out of the piped paths it's the
+                // path itself that the user is after.
+                if self.list_mode == ListMode::Stdin {
+                    let path = selected_entry.path.clone();
+                    self.exit_outcome = Some(RunOutcome::SelectedFile(path));
+                }
+
                 self.should_exit = true;
             }
         }
@@ -359,73 +1656,378 @@ impl App {
         Ok(())
     }
 
-    fn update_filtered_indices(&mut self) {
-        self.entry_list.update_filtered_indices(&self.search_input);
-        self.list_state = ListState::default();
+    fn get_selected_entry(&self) -> Option<&Entry> {
+        let entries = self.entry_list.get_filtered_entries();
+        let index = self.entry_list.clamp_selection(&self.list_state);
+
+        entries.get(index).copied()
     }
 
-    /// Handles a key event with the given key and modifiers, it will perform the appropriate
-    /// action based on the current input mode and registered hotkeys.
-    pub fn handle_key_event(
-        &mut self,
-        key: KeyEvent,
-        modifiers: KeyModifiers,
-    ) -> anyhow::Result<()> {
-        if key.kind != KeyEventKind::Press {
-            return Ok(());
-        }
+    fn copy_to_clipboard<T: AsRef<Path>>(&mut self, path: T) {
+        let text = path.as_ref().to_string_lossy();
 
-        match self.input_mode {
-            InputMode::Search => self.handle_key_event_for_search_mode(key, modifiers),
-            InputMode::Normal => self.handle_key_event_for_normal_mode(key, modifiers),
+        self.status_message = match self.clipboard.set_text(&text) {
+            Result::Ok(()) => Some(format!("Copied '{text}'")),
+            Err(err) => Some(format!("Failed to copy '{text}': {err}")),
+        };
+    }
+
+    /// Searches for the first line of the clipboard's contents, starting a search if there's none
+    /// in progress.
+    fn paste_search_query(&mut self) {
+        let text = match self.clipboard.get_text() {
+            Result::Ok(text) => text,
+            Err(err) => {
+                self.status_message = Some(format!("Failed to read the clipboard: {err}"));
+                return;
+            }
+        };
+
+        if self.input_mode != InputMode::Search {
+            self.session_stats.searches += 1;
+            self.input_mode = InputMode::Search;
+            self.search_contents = false;
+            self.invert_search = false;
         }
+
+        self.search_input
+            .set(text.lines().next().unwrap_or_default().trim());
+        self.update_filtered_indices();
     }
 
-    fn handle_key_event_for_search_mode(
-        &mut self,
-        key: KeyEvent,
-        modifiers: KeyModifiers,
-    ) -> anyhow::Result<()> {
-        // We check for inactivity here so that we can support key sequences
-        if let Some(t) = self.last_key_press_time {
-            if t.elapsed() >= Self::INACTIVITY_TIMEOUT {
-                for key_combo in self.collected_key_combos.iter() {
-                    if let KeyCode::Char(c) = key_combo.key_code {
-                        self.search_input.push(c);
-                    }
-                }
+    fn push_current_directory_to_stack(&mut self) {
+        let Some(file_path) = &self.config.directory_stack_file else {
+            self.status_message = Some("The directory stack file is not configured".into());
+            return;
+        };
 
-                if let KeyCode::Char(c) = key.code {
-                    self.search_input.push(c);
-                }
+        let result = DirectoryStack::load_from_disk(file_path)
+            .and_then(|mut x| x.push(&self.current_directory));
 
-                self.update_filtered_indices();
-                self.collected_key_combos.clear();
-                self.last_key_press_time = None;
+        self.status_message = match result {
+            Result::Ok(()) => Some(format!(
+                "Pushed '{}' onto the directory stack",
+                self.current_directory.display()
+            )),
+            Err(err) => Some(format!("Failed to push onto the directory stack: {err}")),
+        };
+    }
 
-                return Ok(());
-            }
+    /// Bookmarks the selected directory (or removes the bookmark), the bookmarks list is updated
+    /// right away when it's shown.
+    fn toggle_selected_entry_bookmark(&mut self) {
+        let Some(entry) = self.get_selected_entry() else {
+            return;
+        };
+
+        if entry.kind != EntryKind::Directory || self.is_browsing_archive {
+            self.status_message = Some("Only directories can be bookmarked".into());
+            return;
         }
 
-        self.last_key_press_time = Some(Instant::now());
+        let (path, name) = (entry.path.clone(), entry.name.clone());
 
-        let key_combo = KeyCombo::from((key.code, modifiers));
-        self.collected_key_combos.push(key_combo);
+        self.status_message = match self.bookmarks.toggle(&path) {
+            Result::Ok(true) => Some(format!("Bookmarked '{name}'")),
+            Result::Ok(false) => Some(format!("Removed the bookmark of '{name}'")),
+            Err(err) => Some(format!("Failed to save the bookmarks: {err}")),
+        };
 
-        let maybe_node = self
-            .hotkeys_registry
-            .get_hotkey_node(InputMode::Search, &self.collected_key_combos);
+        if self.list_mode == ListMode::Bookmark {
+            let selected = self.list_state.selected();
+            self.list_bookmarks();
+            self.list_state.select(selected);
+        }
+    }
 
-        if let Some(node) = maybe_node {
-            if let Some(action) = node.value {
-                self.collected_key_combos.clear();
-                self.last_key_press_time = None;
+    /// Pins the selected entry to the top of the listing (or unpins it), the selection follows the
+    /// entry to its new place.
+    fn toggle_selected_entry_pin(&mut self) {
+        let Some((path, name)) = self
+            .get_selected_entry()
+            .map(|x| (x.path.clone(), x.name.clone()))
+        else {
+            return;
+        };
+
+        self.status_message = match self.pins.toggle(&path) {
+            Result::Ok(true) => Some(format!("Pinned '{name}'")),
+            Result::Ok(false) => Some(format!("Unpinned '{name}'")),
+            Err(err) => Some(format!("Failed to save the pins: {err}")),
+        };
+
+        // Sorting again in place rather than listing the directory again, which would count as a
+        // visit and run the `on_cd_command`
+        let mut entry_list = std::mem::take(&mut self.entry_list);
+        self.filter_and_sort(&mut entry_list);
+        self.entry_list = entry_list;
+        self.update_filtered_indices();
+
+        let index = self
+            .entry_list
+            .get_filtered_entries()
+            .iter()
+            .position(|x| x.path == path);
+        self.list_state.select(index);
+    }
+
+    /// Lists the current directory again with the same entry selected, when it's still listed.
+    /// Otherwise the selection stays at the same row, as far as the listing goes.
+    fn reload_current_directory_keeping_selection(&mut self) -> anyhow::Result<()> {
+        let selected_path = self.get_selected_entry().map(|x| x.path.clone());
+        let selected_index = self.entry_list.clamp_selection(&self.list_state);
+
+        self.reload_current_directory()?;
+
+        let entries = self.entry_list.get_filtered_entries();
+        let index = entries
+            .iter()
+            .position(|x| Some(&x.path) == selected_path.as_ref())
+            .unwrap_or_else(|| selected_index.min(entries.len().saturating_sub(1)));
+
+        self.list_state.select(Some(index));
+
+        Ok(())
+    }
+
+    /// Restores the full listing and selects the entry that was selected in the filtered one
+    fn reset_filter_keeping_selection(&mut self) {
+        let selected_path = self.get_selected_entry().map(|x| x.path.clone());
+
+        self.update_filtered_indices();
+
+        let index = self
+            .entry_list
+            .get_filtered_entries()
+            .iter()
+            .position(|x| Some(&x.path) == selected_path.as_ref());
+
+        if index.is_some() {
+            self.list_state.select(index);
+        }
+    }
+
+    fn update_filtered_indices(&mut self) {
+        // The complement is only right when all the matches are known, so the limit applies to it
+        // instead
+        let limit = if self.invert_search {
+            None
+        } else {
+            self.config.max_search_results
+        };
+
+        self.entry_list
+            .update_filtered_indices(&self.search_input, limit);
+
+        if self.search_contents {
+            self.entry_list
+                .add_content_matches(&self.search_input, limit);
+        }
+
+        if self.invert_search {
+            self.entry_list
+                .invert_filtered_indices(self.config.max_search_results);
+        }
+        self.list_state = ListState::default();
+
+        self.single_match_time = None;
+
+        if self.config.auto_enter_single.is_some() && self.is_single_directory_match() {
+            self.single_match_time = Some(Instant::now());
+        }
+    }
+
+    fn is_single_directory_match(&self) -> bool {
+        let entries = self.entry_list.get_filtered_entries();
+
+        self.input_mode == InputMode::Search
+            && !self.search_input.is_empty()
+            && entries.len() == 1
+            && entries[0].kind == EntryKind::Directory
+    }
+
+    /// Acts on the only directory left by the search (see `Config::auto_enter_single`), once the
+    /// user has stopped typing for long enough as of `now`.
+    fn auto_enter_single_match(&mut self, now: Instant) -> anyhow::Result<()> {
+        let Some(t) = self.single_match_time else {
+            return Ok(());
+        };
+
+        if now.saturating_duration_since(t) < Self::AUTO_ENTER_DELAY {
+            return Ok(());
+        }
+
+        self.single_match_time = None;
+
+        if !self.is_single_directory_match() || self.is_browsing_archive {
+            return Ok(());
+        }
+
+        self.search_history.push(&self.search_input);
+
+        match self.config.auto_enter_single {
+            Some(AutoEnterSingle::Enter) => {
+                self.change_directory_to_entry_index(0)?;
+                self.input_mode = InputMode::Normal;
+                self.search_input.clear();
+            }
+            Some(AutoEnterSingle::Exit) => {
+                let path = self.entry_list.get_filtered_entries()[0].path.clone();
+
+                self.exit_outcome = Some(RunOutcome::ChangedTo(path));
+                self.should_exit = true;
+            }
+            None => {}
+        }
+
+        Ok(())
+    }
+
+    /// Handles a key event with the given key and modifiers, it will perform the appropriate
+    /// action based on the current input mode and registered hotkeys.
+    pub fn handle_key_event(
+        &mut self,
+        key: KeyEvent,
+        modifiers: KeyModifiers,
+    ) -> anyhow::Result<()> {
+        if key.kind != KeyEventKind::Press {
+            return Ok(());
+        }
+
+        if let Some(confirmation) = self.pending_confirmation.take() {
+            return self.handle_key_event_for_confirmation(confirmation, key);
+        }
+
+        if let Some(command_palette) = self.command_palette.take() {
+            return self.handle_key_event_for_command_palette(command_palette, key, modifiers);
+        }
+
+        // Escape stops computing the size of a directory rather than exiting the app
+        if self.directory_size.is_some()
+            && self.input_mode == InputMode::Normal
+            && key.code == KeyCode::Esc
+        {
+            self.toggle_selected_directory_size();
+            return Ok(());
+        }
+
+        match self.input_mode {
+            InputMode::Search => self.handle_key_event_for_search_mode(key, modifiers),
+            InputMode::Normal => self.handle_key_event_for_normal_mode(key, modifiers),
+        }
+    }
+
+    fn handle_key_event_for_confirmation(
+        &mut self,
+        confirmation: Confirmation,
+        key: KeyEvent,
+    ) -> anyhow::Result<()> {
+        match key.code {
+            KeyCode::Char('y') | KeyCode::Enter => match confirmation {
+                Confirmation::EnterLargeDirectory { path, .. } => {
+                    self.input_mode = InputMode::Normal;
+                    self.change_directory(path)?;
+                }
+            },
+            KeyCode::Char('n') | KeyCode::Esc => {}
+            // Keep waiting for an answer
+            _ => {
+                self.pending_confirmation = Some(confirmation);
+                return Ok(());
+            }
+        }
+
+        self.confirmation_time = None;
+
+        Ok(())
+    }
+
+    fn handle_key_event_for_command_palette(
+        &mut self,
+        mut command_palette: CommandPalette,
+        key: KeyEvent,
+        modifiers: KeyModifiers,
+    ) -> anyhow::Result<()> {
+        match (key.code, modifiers) {
+            (KeyCode::Esc, _) => {}
+            (KeyCode::Enter, _) => {
+                if let Some(action) = command_palette.get_selected_action() {
+                    return self.handle_normal_mode_action(action);
+                }
+            }
+            (KeyCode::Down, _) | (KeyCode::Char('n'), KeyModifiers::CONTROL) => {
+                command_palette.select_next();
+                self.command_palette = Some(command_palette);
+            }
+            (KeyCode::Up, _) | (KeyCode::Char('p'), KeyModifiers::CONTROL) => {
+                command_palette.select_previous();
+                self.command_palette = Some(command_palette);
+            }
+            (KeyCode::Backspace, _) => {
+                command_palette.pop();
+                self.command_palette = Some(command_palette);
+            }
+            (KeyCode::Char(c), KeyModifiers::NONE | KeyModifiers::SHIFT) => {
+                command_palette.push(c);
+                self.command_palette = Some(command_palette);
+            }
+            // Keep the palette open
+            _ => self.command_palette = Some(command_palette),
+        }
+
+        Ok(())
+    }
+
+    fn handle_key_event_for_search_mode(
+        &mut self,
+        key: KeyEvent,
+        modifiers: KeyModifiers,
+    ) -> anyhow::Result<()> {
+        // We check for inactivity here so that we can support key sequences
+        if let Some(t) = self.last_key_press_time {
+            if t.elapsed() >= Self::INACTIVITY_TIMEOUT {
+                for key_combo in self.collected_key_combos.iter() {
+                    if let KeyCode::Char(c) = key_combo.key_code {
+                        self.search_input.push(c);
+                    }
+                }
+
+                if let KeyCode::Char(c) = key.code {
+                    self.search_input.push(c);
+                }
+
+                self.update_filtered_indices();
+                self.collected_key_combos.clear();
+                self.last_key_press_time = None;
+
+                return Ok(());
+            }
+        }
+
+        self.last_key_press_time = Some(Instant::now());
+
+        let key_combo = KeyCombo::from((key.code, modifiers));
+        self.collected_key_combos.push(key_combo);
+
+        let maybe_node = self
+            .hotkeys_registry
+            .get_hotkey_node(InputMode::Search, &self.collected_key_combos);
+
+        if let Some(node) = maybe_node {
+            if let Some(action) = node.value {
+                self.collected_key_combos.clear();
+                self.last_key_press_time = None;
 
                 match action {
                     Action::ChangeDirectoryToEntryWithIndex(index) => {
+                        self.session_stats.quick_jumps += 1;
+                        self.search_history.push(&self.search_input);
                         self.change_directory_to_entry_index(index)?;
                         self.input_mode = InputMode::Normal;
-                        self.search_input.clear();
+
+                        if !self.config.persist_search_on_cd {
+                            self.search_input.clear();
+                        }
                     }
                     Action::SearchInputBackspace => {
                         // Remove character from the search input
@@ -443,19 +2045,76 @@ impl App {
                     Action::SelectPrevious => {
                         self.list_state.select_previous();
                     }
-                    Action::ExitSearchInput => {
+                    Action::AcceptSearchInput => {
+                        self.search_history.push(&self.search_input);
+                        self.input_mode = InputMode::Normal;
+                    }
+                    Action::CancelSearchInput => {
                         self.input_mode = InputMode::Normal;
+                        self.search_input.clear();
+                        self.search_contents = false;
+                        self.invert_search = false;
+                        self.reset_filter_keeping_selection();
+                    }
+                    Action::RecallPreviousSearch => {
+                        if let Some(query) = self.search_history.recall_previous() {
+                            self.search_input.set(query);
+                            self.update_filtered_indices();
+                        }
                     }
-                    Action::ChangeDirectoryToSelectedEntry => {
-                        if let Some(filtered_indices) = &self.entry_list.filtered_indices {
-                            if !filtered_indices.is_empty() {
-                                self.input_mode = InputMode::Normal;
+                    Action::ToggleContentSearch => {
+                        self.search_contents = !self.search_contents;
+                        self.update_filtered_indices();
+                    }
+                    Action::ToggleInvertedSearch => {
+                        self.invert_search = !self.invert_search;
+                        self.update_filtered_indices();
+                    }
+                    Action::PasteSearchQuery => self.paste_search_query(),
+                    Action::DrillIntoFirstDirectory => {
+                        let index = self
+                            .entry_list
+                            .get_filtered_entries()
+                            .iter()
+                            .position(|x| x.kind == EntryKind::Directory);
+
+                        if let Some(index) = index {
+                            let query = self.search_input.value.clone();
+                            let previous_directory = self.current_directory.clone();
+                            self.change_directory_to_entry_index(index)?;
+
+                            // The query was for the previous directory, the search starts over in
+                            // the one we went into. Entering it can be held back though (e.g. by
+                            // the confirmation for large directories), then the query still applies.
+                            if self.current_directory != previous_directory {
+                                self.search_history.push(query);
                                 self.search_input.clear();
-                                let entry_index = self.list_state.selected().unwrap_or_default();
-                                self.change_directory_to_entry_index(entry_index)?;
+                                self.update_filtered_indices();
                             }
                         }
                     }
+                    Action::RecallNextSearch => {
+                        match self.search_history.recall_next() {
+                            Some(query) => self.search_input.set(query),
+                            None => self.search_input.clear(),
+                        }
+
+                        self.update_filtered_indices();
+                    }
+                    Action::ChangeDirectoryToSelectedEntry
+                        if self.entry_list.filtered_indices.is_some()
+                            && !self.entry_list.is_empty() =>
+                    {
+                        self.search_history.push(&self.search_input);
+                        self.input_mode = InputMode::Normal;
+
+                        if !self.config.persist_search_on_cd {
+                            self.search_input.clear();
+                        }
+
+                        let entry_index = self.entry_list.clamp_selection(&self.list_state);
+                        self.change_directory_to_entry_index(entry_index)?;
+                    }
                     _ => {}
                 }
             }
@@ -483,6 +2142,46 @@ impl App {
         Ok(())
     }
 
+    /// Adds the key to the count prefix if it's a digit that belongs to it and returns the updated
+    /// count. A count can't start with `0` and digits that are bound to an entry (the quick-jump
+    /// hotkeys) jump to the entry instead of starting a count.
+    fn collect_count_prefix(
+        &mut self,
+        key_code: KeyCode,
+        modifiers: KeyModifiers,
+    ) -> Option<usize> {
+        let KeyCode::Char(c) = key_code else {
+            return None;
+        };
+
+        let digit = c.to_digit(10)? as usize;
+
+        if modifiers != KeyModifiers::NONE || !self.collected_key_combos.is_empty() {
+            return None;
+        }
+
+        if self.count_prefix.is_none() {
+            let is_hotkey = self
+                .hotkeys_registry
+                .get_hotkey_node(InputMode::Normal, &[KeyCombo::from((key_code, modifiers))])
+                .is_some();
+
+            if digit == 0 || is_hotkey {
+                return None;
+            }
+        }
+
+        let count = self
+            .count_prefix
+            .unwrap_or_default()
+            .saturating_mul(10)
+            .saturating_add(digit);
+
+        self.count_prefix = Some(count);
+
+        Some(count)
+    }
+
     fn handle_key_event_for_normal_mode(
         &mut self,
         key: KeyEvent,
@@ -497,22 +2196,50 @@ impl App {
         }
 
         self.last_key_press_time = Some(Instant::now());
+        self.status_message = None;
 
-        self.collected_key_combos
-            .push(KeyCombo::from((key.code, modifiers)));
+        if let Some(count) = self.collect_count_prefix(key.code, modifiers) {
+            self.status_message = Some(format!("Go to row {count}"));
+            return Ok(());
+        }
 
-        let maybe_action = self
-            .hotkeys_registry
-            .get_hotkey_value(InputMode::Normal, &self.collected_key_combos);
+        let action = match self.count_prefix.take() {
+            Some(count) if key.code == KeyCode::Enter => {
+                self.last_key_press_time = None;
+                Action::GoToIndex(count.saturating_sub(1))
+            }
+            _ => {
+                self.collected_key_combos
+                    .push(KeyCombo::from((key.code, modifiers)));
 
-        let Some(&action) = maybe_action else {
-            return Ok(());
+                let maybe_action = self
+                    .hotkeys_registry
+                    .get_hotkey_value(InputMode::Normal, &self.collected_key_combos);
+
+                let Some(&action) = maybe_action else {
+                    return Ok(());
+                };
+
+                self.collected_key_combos.clear();
+                self.last_key_press_time = None;
+
+                action
+            }
         };
 
-        self.collected_key_combos.clear();
-        self.last_key_press_time = None;
+        self.handle_normal_mode_action(action)
+    }
 
+    fn handle_normal_mode_action(&mut self, action: Action) -> anyhow::Result<()> {
         match action {
+            Action::SelectNext if self.show_help => {
+                // How far it can be scrolled depends on the size of the popup, see
+                // `render_help_popup`
+                self.help_scroll = self.help_scroll.saturating_add(1);
+            }
+            Action::SelectPrevious if self.show_help => {
+                self.help_scroll = self.help_scroll.saturating_sub(1);
+            }
             Action::SelectNext => {
                 self.show_help = false;
                 self.list_state.select_next();
@@ -529,19 +2256,56 @@ impl App {
                 self.show_help = false;
                 self.list_state.select_last();
             }
+            Action::SelectNextColumn | Action::SelectPreviousColumn => {
+                self.show_help = false;
+
+                // The columns only exist while the entries are rendered in a grid
+                if let Some(rows) = self.grid_rows {
+                    let selected = self.entry_list.clamp_selection(&self.list_state);
+                    let last_index = self.entry_list.filtered_len().saturating_sub(1);
+
+                    let index = if action == Action::SelectNextColumn {
+                        (selected + rows).min(last_index)
+                    } else {
+                        selected.saturating_sub(rows)
+                    };
+
+                    self.list_state.select(Some(index));
+                }
+            }
             Action::SwitchToListMode(mode) => {
                 self.show_help = false;
                 self.change_list_mode(mode)?;
             }
             Action::ToggleHelp => {
                 self.show_help = !self.show_help;
+                self.help_scroll = 0;
+            }
+            Action::ToggleSessionStats => {
+                self.show_help = false;
+                self.show_session_stats = !self.show_session_stats;
+            }
+            Action::OpenCommandPalette => {
+                self.show_help = false;
+                self.command_palette = Some(CommandPalette::new(&self.hotkeys_registry));
             }
             Action::SwitchToInputMode(mode) => {
                 self.show_help = false;
+
+                if mode == InputMode::Search {
+                    self.session_stats.searches += 1;
+                }
+
                 self.input_mode = mode;
                 self.search_input.clear();
+                self.search_contents = false;
+                self.invert_search = false;
                 self.update_filtered_indices();
             }
+            Action::PasteSearchQuery => {
+                self.show_help = false;
+                self.paste_search_query();
+            }
             Action::ResetSearchInput => {
                 // clear the search input while in search mode
                 self.search_input.clear();
@@ -549,8 +2313,11 @@ impl App {
             }
             Action::ChangeDirectoryToSelectedEntry => {
                 self.show_help = false;
-                let entry_index = self.list_state.selected().unwrap_or_default();
-                self.change_directory_to_entry_index(entry_index)?;
+
+                if !self.entry_list.is_empty() {
+                    let entry_index = self.entry_list.clamp_selection(&self.list_state);
+                    self.change_directory_to_entry_index(entry_index)?;
+                }
             }
             Action::ChangeDirectoryToParent => {
                 self.show_help = false;
@@ -559,43 +2326,306 @@ impl App {
                     self.change_directory(parent)?;
                 }
             }
+            Action::ToggleRepositoryRoot => {
+                self.show_help = false;
+
+                match self.repository_root_jump.take() {
+                    Some((root, origin)) if root == self.current_directory => {
+                        self.change_directory(origin)?;
+                    }
+                    _ => match find_repository_root(&self.current_directory) {
+                        Some(root) if root == self.current_directory => {
+                            self.status_message = Some("Already at the repository root".into());
+                        }
+                        Some(root) => {
+                            let root = root.to_path_buf();
+                            let origin = self.current_directory.clone();
+
+                            self.change_directory(&root)?;
+                            self.repository_root_jump = Some((root, origin));
+                        }
+                        None => {
+                            self.status_message = Some("Not inside a git repository".into());
+                        }
+                    },
+                }
+            }
+            Action::ChangeDirectoryToNextSibling | Action::ChangeDirectoryToPreviousSibling => {
+                self.show_help = false;
+
+                // The siblings only make sense for a directory that actually exists
+                if self.list_mode == ListMode::Directory && !self.is_browsing_archive {
+                    self.change_directory_to_sibling(
+                        action == Action::ChangeDirectoryToNextSibling,
+                    )?;
+                }
+            }
             Action::ChangeDirectoryToEntryWithIndex(index) => {
                 self.show_help = false;
+                self.session_stats.quick_jumps += 1;
                 self.change_directory_to_entry_index(index)?;
             }
-            Action::Exit => {
-                if self.show_help {
-                    self.show_help = false;
-                } else if self.search_input.is_empty() {
-                    self.should_exit = true;
-                } else {
-                    self.search_input.clear();
-                    self.update_filtered_indices();
+            Action::GoToIndex(index) => {
+                self.show_help = false;
+
+                // Out of range indices select the last entry
+                if !self.entry_list.is_empty() {
+                    let last_index = self.entry_list.filtered_len() - 1;
+                    self.list_state.select(Some(index.min(last_index)));
                 }
             }
-            // Ignore the rest
-            _ => {}
-        }
+            Action::EnterSelectedEntryAndExit => {
+                self.show_help = false;
 
-        Ok(())
-    }
+                if self.is_browsing_archive {
+                    self.status_message = Some("Archive members can't be opened".into());
+                    return Ok(());
+                }
 
-    pub fn get_sub_header_title(&self) -> String {
+                // There's no need to list the directory since we're exiting right away
+                if let Some(entry) = self.get_selected_entry() {
+                    let outcome = match entry.kind {
+                        EntryKind::Directory => RunOutcome::ChangedTo(entry.path.clone()),
+                        EntryKind::File { .. } => RunOutcome::SelectedFile(entry.path.clone()),
+                    };
+
+                    self.exit_outcome = Some(outcome);
+                    self.should_exit = true;
+                }
+            }
+            Action::OpenShellInSelectedEntry => {
+                self.show_help = false;
+
+                if self.is_browsing_archive {
+                    self.status_message = Some("Archive members can't be opened".into());
+                    return Ok(());
+                }
+
+                // A shell can't be opened in a file, so we fall back to the directory that has it
+                let path = match self.get_selected_entry() {
+                    Some(entry) if entry.kind == EntryKind::Directory => entry.path.clone(),
+                    _ => self.get_exit_directory().to_path_buf(),
+                };
+
+                self.exit_outcome = Some(RunOutcome::OpenShellIn(path));
+                self.should_exit = true;
+            }
+            Action::ToggleHiddenFiles => {
+                self.show_help = false;
+                self.config.hide_hidden_files = !self.config.hide_hidden_files;
+
+                if self.list_mode == ListMode::Directory {
+                    self.reload_current_directory_keeping_selection()?;
+                }
+            }
+            Action::ToggleQuickJumpHints => {
+                self.show_help = false;
+                self.config.hide_quick_jump_hints = !self.config.hide_quick_jump_hints;
+            }
+            Action::ToggleGitIgnoredOnly => {
+                self.show_help = false;
+
+                if self.list_mode != ListMode::Directory || self.is_browsing_archive {
+                    return Ok(());
+                }
+
+                if !self.show_only_git_ignored
+                    && find_repository_root(&self.current_directory).is_none()
+                {
+                    self.status_message = Some("Not inside a git repository".into());
+                    return Ok(());
+                }
+
+                self.show_only_git_ignored = !self.show_only_git_ignored;
+                self.reload_current_directory()?;
+
+                self.status_message = Some(if self.show_only_git_ignored {
+                    "Showing only the git-ignored entries".into()
+                } else {
+                    "Showing all the entries".into()
+                });
+            }
+            Action::ToggleSortDirection => {
+                self.show_help = false;
+                self.config.sort_descending = !self.config.sort_descending;
+
+                if self.list_mode == ListMode::Directory {
+                    self.reload_current_directory()?;
+                }
+            }
+            Action::CycleDirectoryGrouping => {
+                self.show_help = false;
+                self.config.directory_grouping = self.config.directory_grouping.next();
+
+                if self.list_mode == ListMode::Directory {
+                    self.reload_current_directory()?;
+                }
+
+                self.status_message = Some(match self.config.directory_grouping {
+                    DirectoryGrouping::First => "Listing the directories first".into(),
+                    DirectoryGrouping::Last => "Listing the directories last".into(),
+                    DirectoryGrouping::Mixed => "Listing the directories mixed with files".into(),
+                });
+            }
+            Action::TogglePathBasenames => {
+                self.show_help = false;
+
+                // The directory listing always shows the names, there's nothing to toggle
+                if self.list_mode != ListMode::Directory {
+                    self.show_path_basenames = !self.show_path_basenames;
+                    self.entry_list.set_path_names(self.show_path_basenames);
+                    self.update_filtered_indices();
+                }
+            }
+            Action::PushCurrentDirectoryToStack => {
+                self.show_help = false;
+                self.push_current_directory_to_stack();
+            }
+            Action::ComputeSelectedDirectorySize => {
+                self.show_help = false;
+                self.toggle_selected_directory_size();
+            }
+            Action::SetFavoriteSlot(slot) => {
+                self.show_help = false;
+
+                // Only an actual directory can be gone back to
+                if self.list_mode != ListMode::Directory || self.is_browsing_archive {
+                    self.status_message = Some("Only a directory can be saved to a slot".into());
+                    return Ok(());
+                }
+
+                self.status_message = match self.favorites.set(slot, &self.current_directory) {
+                    Result::Ok(()) => Some(format!("Saved the directory to slot {slot}")),
+                    Err(err) => Some(format!("Failed to save the favorites: {err}")),
+                };
+            }
+            Action::ChangeDirectoryToFavoriteSlot(slot) => {
+                self.show_help = false;
+
+                let Some(path) = self.favorites.get(slot).map(Path::to_path_buf) else {
+                    self.status_message = Some(format!("Slot {slot} is empty"));
+                    return Ok(());
+                };
+
+                if let Err(err) = self.change_directory(&path) {
+                    self.status_message = Some(format!("Failed to open slot {slot}: {err}"));
+                }
+            }
+            Action::ToggleBookmark => {
+                self.show_help = false;
+                self.toggle_selected_entry_bookmark();
+            }
+            Action::TogglePinnedEntry => {
+                self.show_help = false;
+
+                // Pins only make sense in the directory listing, the other lists aren't sorted
+                if self.list_mode == ListMode::Directory && !self.is_browsing_archive {
+                    self.toggle_selected_entry_pin();
+                }
+            }
+            Action::CopySelectedEntryPath => {
+                self.show_help = false;
+
+                if let Some(path) = self.get_selected_entry().map(|x| x.path.clone()) {
+                    self.copy_to_clipboard(path);
+                }
+            }
+            Action::CopySelectedEntryRelativePath => {
+                self.show_help = false;
+
+                if let Some(path) = self
+                    .get_selected_entry()
+                    .map(|x| x.path_relative_to(&self.current_directory).to_path_buf())
+                {
+                    self.copy_to_clipboard(path);
+                }
+            }
+            Action::CopySelectedEntryName => {
+                self.show_help = false;
+
+                // The displayed name can be a full path (outside of the directory listing), so we
+                // take the last component of the path instead
+                if let Some(name) = self
+                    .get_selected_entry()
+                    .and_then(|x| x.path.file_name())
+                    .map(PathBuf::from)
+                {
+                    self.copy_to_clipboard(name);
+                }
+            }
+            Action::CopySelectedEntryUri => {
+                self.show_help = false;
+
+                if let Some(uri) = self.get_selected_entry().map(|x| file_uri(&x.path)) {
+                    self.copy_to_clipboard(uri);
+                }
+            }
+            Action::Exit => {
+                if self.show_help {
+                    self.show_help = false;
+                } else if self.show_session_stats {
+                    self.show_session_stats = false;
+                } else if self.search_input.is_empty() {
+                    self.should_exit = true;
+                } else {
+                    self.search_input.clear();
+                    self.update_filtered_indices();
+                }
+            }
+            Action::ExitWithoutChangingDirectory => {
+                self.should_exit = true;
+                self.exit_outcome = Some(RunOutcome::Cancelled);
+            }
+            // Ignore the rest
+            _ => {}
+        }
+
+        Ok(())
+    }
+
+    pub fn get_sub_header_title(&self) -> String {
         match &self.list_mode {
             ListMode::Directory => self.current_directory.to_string_lossy().into_owned(),
             ListMode::Frecent => "Most accessed paths".into(),
+            ListMode::Stdin => "Piped paths".into(),
+            ListMode::Bookmark => "Bookmarks".into(),
         }
     }
 
-    fn render_header(area: Rect, buf: &mut Buffer) {
-        let app_version = env!("CARGO_PKG_VERSION");
+    fn render_header(&self, area: Rect, buf: &mut Buffer) {
+        let line = match &self.config.header {
+            HeaderContent::AppVersion => {
+                let app_version = env!("CARGO_PKG_VERSION");
 
-        let line = Line::from(vec![
-            Span::styled("Tiny FE", Style::default().bold()),
-            Span::styled(format!(" v{}", app_version), Style::default().dark_gray()),
-        ]);
+                Line::from(vec![
+                    Span::styled("Tiny FE", Style::default().bold()),
+                    Span::styled(format!(" v{}", app_version), Style::default().dark_gray()),
+                ])
+            }
+            HeaderContent::CurrentDirectory => Line::styled(
+                self.get_exit_directory().to_string_lossy(),
+                Style::default().bold(),
+            ),
+            HeaderContent::ListMode => {
+                let mode = match self.list_mode {
+                    ListMode::Directory => "Directory listing",
+                    ListMode::Frecent => "Most accessed paths",
+                    ListMode::Stdin => "Piped paths",
+                    ListMode::Bookmark => "Bookmarks",
+                };
+
+                Line::styled(mode, Style::default().bold())
+            }
+            HeaderContent::Custom(text) => Line::styled(text.as_str(), Style::default().bold()),
+        };
+
+        let alignment = match self.config.header_alignment {
+            HeaderAlignment::Left => Alignment::Left,
+            HeaderAlignment::Center => Alignment::Center,
+            HeaderAlignment::Right => Alignment::Right,
+        };
 
-        Paragraph::new(line).centered().render(area, buf);
+        Paragraph::new(line).alignment(alignment).render(area, buf);
     }
 
     fn render_selected_tab_title(&mut self, area: Rect, buf: &mut Buffer) {
@@ -606,10 +2636,55 @@ impl App {
         ]);
 
         Paragraph::new(Text::from(vec![line])).render(area, buf);
+
+        if self.list_mode == ListMode::Directory {
+            let sort_direction = if self.config.sort_descending {
+                "Z-A ↓ "
+            } else {
+                "A-Z ↑ "
+            };
+
+            // The default grouping isn't shown, to keep the title quiet
+            let grouping = match self.config.directory_grouping {
+                DirectoryGrouping::First => "",
+                DirectoryGrouping::Last => "dirs last · ",
+                DirectoryGrouping::Mixed => "dirs mixed · ",
+            };
+
+            Paragraph::new(format!("{grouping}{sort_direction}"))
+                .style(Style::default().dark_gray())
+                .right_aligned()
+                .render(area, buf);
+        }
+    }
+
+    /// Renders the parent of the current directory along with a hint on how to go up to it
+    fn render_parent_context(&self, area: Rect, buf: &mut Buffer) {
+        if area.is_empty() {
+            return;
+        }
+
+        let line = match self.get_exit_directory().parent() {
+            Some(parent) => {
+                let name = parent
+                    .file_name()
+                    .map_or_else(|| parent.to_string_lossy(), |x| x.to_string_lossy());
+
+                Line::from(vec![
+                    Span::raw(" ../ "),
+                    Span::styled(name, Style::default().fg(Color::Blue)),
+                    Span::styled("  (h to go up)", Style::default().dark_gray()),
+                ])
+            }
+            None => Line::styled(" (at the root)", Style::default().dark_gray()),
+        };
+
+        line.render(area, buf);
     }
 
     fn render_footer(&mut self, area: Rect, buf: &mut Buffer) {
-        let input = format!(" /{input}", input = self.search_input);
+        let prompt = &self.config.theme.search_prompt;
+        let input = format!(" {prompt}{input}", input = self.search_input);
 
         if self.input_mode == InputMode::Search {
             Paragraph::new(input)
@@ -617,28 +2692,67 @@ impl App {
                 .alignment(Alignment::Left)
                 .render(area, buf);
 
-            // Calculate the cursor poisition and account for the space and '/' characters
-            let cursor_x = area.x + 2 + self.search_input.index as u16;
+            self.render_search_indicators(area, buf);
+
+            // Calculate the cursor poisition and account for the space and the prompt, the prompt can
+            // be wider than a single column
+            let cursor_x = area.x + 1 + prompt.width() as u16 + self.search_input.index as u16;
             let cursor_y = area.y;
 
             self.cursor_position = Some((cursor_x, cursor_y));
         } else {
-            if self.search_input.is_empty() {
+            if let Some(status_message) = &self.status_message {
+                Paragraph::new(status_message.as_str())
+                    .style(Style::default().fg(Color::Green))
+                    .left_aligned()
+                    .render(area, buf);
+            } else if let Some(calculation) = &self.directory_size {
+                Paragraph::new(format!(
+                    "Computing the size of '{}'… (D or Esc to stop)",
+                    calculation.path.display()
+                ))
+                .style(Style::default().fg(Color::Yellow))
+                .left_aligned()
+                .render(area, buf);
+            } else if let Some(name) = self.get_selected_truncated_name() {
+                // The selected entry's name doesn't fit in the list, so we show it in full here
+                Paragraph::new(format!(" {name}"))
+                    .style(Style::default().fg(Color::White))
+                    .left_aligned()
+                    .render(area, buf);
+            } else if self.search_input.is_empty() {
                 let select_index = match self.list_mode {
                     ListMode::Directory => 0,
                     ListMode::Frecent => 1,
+                    ListMode::Bookmark => 2,
+                    // None of the tabs, the piped paths can't be switched to
+                    ListMode::Stdin => usize::MAX,
                 };
 
                 let block = Block::default().borders(Borders::NONE);
                 block.render(area, buf);
 
+                let titles = ["(d)irectory", "(f)recent", "(b)ookmarks"];
+                let help_hint = "Press ? for help";
+
+                // Each tab is padded with a space on both sides and they're separated by a divider.
+                // The hint is dropped when it can't be kept apart from the tabs by at least a space.
+                let tabs_width =
+                    titles.iter().map(|x| x.width() + 2).sum::<usize>() + titles.len() - 1;
+                let help_hint_width =
+                    if 6 + tabs_width + 1 + help_hint.width() <= area.width as usize {
+                        help_hint.width() + 1
+                    } else {
+                        0
+                    };
+
                 let chunks = Layout::default()
                     .direction(Direction::Horizontal)
                     .constraints(
                         [
                             Constraint::Length(6),
                             Constraint::Min(1),
-                            Constraint::Length(16),
+                            Constraint::Length(help_hint_width as u16),
                         ]
                         .as_ref(),
                     )
@@ -651,20 +2765,117 @@ impl App {
                 .alignment(Alignment::Left)
                 .render(chunks[0], buf);
 
-                Tabs::new(["(d)irectory", "(f)recent"])
+                Tabs::new(titles)
                     .highlight_style(Style::default().fg(Color::Green))
                     .select(select_index)
                     .render(chunks[1], buf);
 
-                Paragraph::new("Press ? for help ").render(chunks[2], buf);
+                if help_hint_width > 0 {
+                    Paragraph::new(help_hint)
+                        .right_aligned()
+                        .render(chunks[2], buf);
+                }
             } else {
                 Paragraph::new(input).left_aligned().render(area, buf);
+                self.render_search_indicators(area, buf);
             }
 
             self.cursor_position = None;
         }
     }
 
+    fn render_search_indicators(&self, area: Rect, buf: &mut Buffer) {
+        let mut indicators = Vec::new();
+
+        if self.search_contents {
+            indicators.push(String::from("searching file contents"));
+        }
+
+        if self.invert_search {
+            indicators.push(String::from("inverted"));
+        }
+
+        if self.entry_list.is_capped {
+            let count = self.entry_list.filtered_len();
+            indicators.push(format!("showing the first {count} results"));
+        }
+
+        if indicators.is_empty() {
+            return;
+        }
+
+        Paragraph::new(format!("{} ", indicators.join(" · ")))
+            .style(Style::default().fg(Color::DarkGray))
+            .right_aligned()
+            .render(area, buf);
+    }
+
+    /// Returns the full name of the selected entry if it's truncated in the list
+    fn get_selected_truncated_name(&self) -> Option<&str> {
+        let max_name_width = self.config.max_name_width?;
+
+        self.get_selected_entry()
+            .map(|x| x.name.as_str())
+            .filter(|x| x.width() > max_name_width)
+    }
+
+    /// Renders the entries in columns like `ls` does, filling the columns from top to bottom, and
+    /// returns the number of rows. Returns `None` (without rendering anything) when the entries
+    /// don't fit in more than one column, so that the regular list can be rendered instead.
+    fn render_grid(
+        lines: &[Line],
+        selected: usize,
+        block: &Block,
+        area: Rect,
+        buf: &mut Buffer,
+    ) -> Option<usize> {
+        const COLUMN_GAP: usize = 2;
+
+        let inner_area = block.inner(area);
+
+        // Every cell starts with the space for the selection symbol
+        let cell_width = 1 + lines.iter().map(Line::width).max().unwrap_or_default();
+        let columns = (inner_area.width as usize / (cell_width + COLUMN_GAP)).min(lines.len());
+
+        if columns < 2 || inner_area.height == 0 {
+            return None;
+        }
+
+        let rows = lines.len().div_ceil(columns);
+
+        // Scroll just enough to keep the row of the selected entry visible
+        let height = inner_area.height as usize;
+        let row_offset = (selected % rows).saturating_sub(height - 1);
+
+        block.render(area, buf);
+
+        for (i, line) in lines.iter().enumerate() {
+            let (column, row) = (i / rows, i % rows);
+
+            if row < row_offset || row - row_offset >= height {
+                continue;
+            }
+
+            let cell_area = Rect {
+                x: inner_area.x + (column * (cell_width + COLUMN_GAP)) as u16,
+                y: inner_area.y + (row - row_offset) as u16,
+                width: cell_width as u16,
+                height: 1,
+            };
+            let [symbol_area, line_area] =
+                Layout::horizontal([Constraint::Length(1), Constraint::Fill(1)]).areas(cell_area);
+
+            line.render(line_area, buf);
+
+            if i == selected {
+                Span::raw(">").render(symbol_area, buf);
+                buf.set_style(cell_area, Self::SELECTED_ENTRY_STYLE);
+            }
+        }
+
+        Some(rows)
+    }
+
     fn render_list(&mut self, area: Rect, buf: &mut Buffer) {
         let block = Block::new()
             .borders(Borders::ALL)
@@ -673,25 +2884,128 @@ impl App {
 
         let entries = self.entry_list.get_filtered_entries();
 
+        // Entries named by their basename (e.g. the most accessed paths) can share a name, those
+        // are told apart by their parent
+        let mut name_counts: HashMap<&str, usize> = HashMap::new();
+        for entry in entries.iter() {
+            *name_counts.entry(&entry.name).or_default() += 1;
+        }
+
         let mut entry_render_data: Vec<EntryRenderData> = entries
             .into_iter()
-            .map(|x| EntryRenderData::from_entry(x, &self.search_input))
+            .map(|x| {
+                let mut render_data = EntryRenderData::from_entry(x, &self.search_input);
+
+                if name_counts
+                    .get(x.name.as_str())
+                    .is_some_and(|&count| count > 1)
+                {
+                    render_data.parent_hint = x
+                        .path
+                        .parent()
+                        .and_then(Path::file_name)
+                        .map(|x| x.to_string_lossy().into_owned());
+                }
+
+                // The metadata of the entry is still being read in the background
+                let is_loading = self
+                    .metadata_loader
+                    .as_ref()
+                    .is_some_and(|loader| loader.is_pending(&x.path));
+
+                if self.config.classify {
+                    render_data.type_indicator = match x.get_type_indicator() {
+                        None if is_loading => Self::LOADING_PLACEHOLDER.chars().next(),
+                        type_indicator => type_indicator,
+                    };
+                }
+
+                render_data.search_hit_style = self.config.theme.search_hit;
+                render_data.hide_trailing_slash = self.config.hide_directory_slash;
+                render_data.content_snippet = self.entry_list.get_content_snippet(x);
+
+                if let Some(format) = self.config.permissions_format {
+                    render_data.permissions = if is_loading {
+                        let width = format_permissions(0, format).len();
+                        Some(format!("{:<width$}", Self::LOADING_PLACEHOLDER))
+                    } else {
+                        x.permissions.map(|m| format_permissions(m, format))
+                    };
+                }
+
+                if self.config.show_owner {
+                    render_data.owner = if is_loading {
+                        Some(Self::LOADING_PLACEHOLDER.into())
+                    } else {
+                        x.owner_ids
+                            .map(|(uid, gid)| self.owner_names.get_owner(uid, gid))
+                    };
+                }
+
+                if let EntryKind::File {
+                    extension: Some(extension),
+                } = &x.kind
+                {
+                    render_data.file_color = self.config.theme.get_extension_color(extension);
+                }
+
+                render_data
+            })
             .collect();
 
+        // The owners differ in width, we pad them so that the names stay aligned
+        let owner_width = entry_render_data
+            .iter()
+            .filter_map(|x| x.owner.as_ref().map(|x| x.width()))
+            .max()
+            .unwrap_or_default();
+
+        for owner in entry_render_data
+            .iter_mut()
+            .filter_map(|x| x.owner.as_mut())
+        {
+            let padding = owner_width - owner.width();
+            owner.push_str(&" ".repeat(padding));
+        }
+
+        if let Some(max_name_width) = self.config.max_name_width {
+            for x in entry_render_data.iter_mut() {
+                x.truncate(max_name_width);
+            }
+        }
+
         if self.input_mode == InputMode::Normal
             || (self.input_mode == InputMode::Search && !self.search_input.is_empty())
         {
             self.hotkeys_registry
                 .assign_hotkeys(&mut entry_render_data, &PREFERRED_KEY_COMBOS_IN_ORDER);
+
+            // The keys stay assigned, so jumping still works for those who know them by heart
+            if self.config.hide_quick_jump_hints {
+                for x in entry_render_data.iter_mut() {
+                    x.key_combo_sequence = None;
+                }
+            }
         } else {
             self.hotkeys_registry.clear_entry_hotkeys();
         }
 
-        let items: Vec<ListItem> = entry_render_data.into_iter().map(ListItem::from).collect();
+        let lines: Vec<Line> = entry_render_data.into_iter().map(Line::from).collect();
 
-        if items.is_empty() {
+        self.grid_rows = None;
+
+        if lines.is_empty() {
             let empty_results_text = if self.search_input.is_empty() {
-                String::from("Nothing here but digital thumbleweeds.")
+                match self.list_mode {
+                    ListMode::Directory => String::from("Nothing here but digital thumbleweeds."),
+                    ListMode::Frecent => String::from(
+                        "No frecent directories yet, press Ctrl + d to browse the current directory.",
+                    ),
+                    ListMode::Stdin => String::from("No paths have been piped in."),
+                    ListMode::Bookmark => String::from(
+                        "No bookmarks yet, press B on a directory to bookmark it.",
+                    ),
+                }
             } else {
                 format!("No results found for '{query}'", query = self.search_input)
             };
@@ -699,23 +3013,35 @@ impl App {
             Paragraph::new(empty_results_text)
                 .block(block)
                 .render(area, buf);
-        } else {
-            // Create a List from all list items and highlight the currently selected one
-            let list = List::new(items)
-                .block(block)
-                .highlight_style(Style::new().bg(Color::Gray).fg(Color::Black))
-                .highlight_symbol(">")
-                .highlight_spacing(HighlightSpacing::Always);
 
-            // If no item is selected, preselect the first item
-            if self.list_state.selected().is_none() {
-                self.list_state.select_first();
+            return;
+        }
+
+        if self.config.grid {
+            let selected = self.entry_list.clamp_selection(&self.list_state);
+
+            if let Some(rows) = Self::render_grid(&lines, selected, &block, area, buf) {
+                self.list_state.select(Some(selected));
+                self.grid_rows = Some(rows);
+                return;
             }
+        }
+
+        // Create a List from all list items and highlight the currently selected one
+        let list = List::new(lines)
+            .block(block)
+            .highlight_style(Self::SELECTED_ENTRY_STYLE)
+            .highlight_symbol(">")
+            .highlight_spacing(HighlightSpacing::Always);
 
-            // We need to disambiguate this trait method as both `Widget` and `StatefulWidget` share
-            // the same method name `render`.
-            StatefulWidget::render(list, area, buf, &mut self.list_state);
+        // If no item is selected, preselect the first item
+        if self.list_state.selected().is_none() {
+            self.list_state.select_first();
         }
+
+        // We need to disambiguate this trait method as both `Widget` and `StatefulWidget` share
+        // the same method name `render`.
+        StatefulWidget::render(list, area, buf, &mut self.list_state);
     }
 }
 
@@ -732,153 +3058,2294 @@ impl Widget for &mut App {
         ])
         .areas(area);
 
-        let [list_area] = Layout::vertical([Constraint::Fill(1)]).areas(main_area);
+        // The parent context only makes sense when listing a directory
+        let context_height =
+            u16::from(self.config.show_parent_context && self.list_mode == ListMode::Directory);
+        let [context_area, list_area] =
+            Layout::vertical([Constraint::Length(context_height), Constraint::Fill(1)])
+                .areas(main_area);
+
+        self.render_header(header_area, buf);
+        self.render_parent_context(context_area, buf);
+
+        self.render_footer(footer_area, buf);
+        self.render_selected_tab_title(selected_tab_title_area, buf);
+        self.render_list(list_area, buf);
+
+        if self.show_help {
+            self.render_help_popup(buf);
+        }
+
+        if self.show_session_stats {
+            self.render_session_stats_popup(Instant::now(), buf);
+        }
+
+        if let Some(command_palette) = &mut self.command_palette {
+            command_palette.render(buf);
+        }
+
+        if let Some(confirmation) = &self.pending_confirmation {
+            App::render_confirmation_popup(
+                confirmation,
+                self.get_confirmation_time_left(Instant::now()),
+                buf,
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{cell::RefCell, collections::HashMap, fs::File, rc::Rc};
+
+    use super::*;
+
+    use insta::assert_snapshot;
+    use ratatui::{backend::TestBackend, Terminal};
+
+    use crate::{config::PermissionsFormat, entry::read_paths, theme::Theme};
+
+    /// A clipboard that keeps the copied text in memory, so that we can assert on it
+    #[derive(Debug, Default, Clone)]
+    struct TestClipboard {
+        text: Rc<RefCell<Option<String>>>,
+    }
+
+    impl Clipboard for TestClipboard {
+        fn set_text(&mut self, text: &str) -> anyhow::Result<()> {
+            *self.text.borrow_mut() = Some(text.to_string());
+            Ok(())
+        }
+
+        fn get_text(&mut self) -> anyhow::Result<String> {
+            self.text
+                .borrow()
+                .clone()
+                .ok_or_else(|| anyhow::anyhow!("The clipboard is empty"))
+        }
+    }
+
+    /// A spawner that records the spawned commands instead of running them
+    #[derive(Debug, Default, Clone)]
+    struct TestCommandSpawner {
+        spawned: Rc<RefCell<Vec<(String, PathBuf)>>>,
+        should_fail: bool,
+    }
+
+    impl CommandSpawner for TestCommandSpawner {
+        fn spawn(&mut self, command: &str, path: &Path) -> anyhow::Result<()> {
+            anyhow::ensure!(!self.should_fail, "spawn failed");
+
+            self.spawned
+                .borrow_mut()
+                .push((command.to_string(), path.to_path_buf()));
+            Ok(())
+        }
+    }
+
+    fn create_test_app() -> App {
+        App::from_entries(
+            vec![
+                Entry {
+                    path: PathBuf::from("/home/user/.git/"),
+                    kind: EntryKind::Directory,
+                    name: ".git".into(),
+                    name_lower: ".git".into(),
+                    is_symlink: false,
+                    is_executable: false,
+                    permissions: None,
+                    owner_ids: None,
+                },
+                Entry {
+                    path: PathBuf::from("/home/user/dir1/"),
+                    kind: EntryKind::Directory,
+                    name: "dir1".into(),
+                    name_lower: "dir1".into(),
+                    is_symlink: false,
+                    is_executable: false,
+                    permissions: None,
+                    owner_ids: None,
+                },
+                Entry {
+                    path: PathBuf::from("/home/user/.gitignore"),
+                    kind: EntryKind::File { extension: None },
+                    name: ".gitignore".into(),
+                    name_lower: ".gitignore".into(),
+                    is_symlink: false,
+                    is_executable: false,
+                    permissions: None,
+                    owner_ids: None,
+                },
+                Entry {
+                    path: PathBuf::from("/home/user/Cargo.toml"),
+                    kind: EntryKind::File {
+                        extension: Some("toml".into()),
+                    },
+                    name: "Cargo.toml".into(),
+                    name_lower: "cargo.toml".into(),
+                    is_symlink: false,
+                    is_executable: false,
+                    permissions: None,
+                    owner_ids: None,
+                },
+            ],
+            PathBuf::from("/home/user"),
+        )
+    }
+
+    #[test]
+    fn renders_correctly() {
+        let mut app = create_test_app();
+        let mut terminal = Terminal::new(TestBackend::new(80, 9)).unwrap();
+
+        terminal
+            .draw(|frame| frame.render_widget(&mut app, frame.area()))
+            .unwrap();
+
+        assert_snapshot!(terminal.backend());
+    }
+
+    #[test]
+    fn renders_left_aligned_path_header() {
+        let mut app = App {
+            config: Config {
+                header: HeaderContent::CurrentDirectory,
+                header_alignment: HeaderAlignment::Left,
+                ..Default::default()
+            },
+            ..create_test_app()
+        };
+        let mut terminal = Terminal::new(TestBackend::new(80, 9)).unwrap();
+
+        terminal
+            .draw(|frame| frame.render_widget(&mut app, frame.area()))
+            .unwrap();
+
+        assert_snapshot!(terminal.backend());
+    }
+
+    #[test]
+    fn renders_directories_without_trailing_slash() {
+        let mut app = App {
+            config: Config {
+                hide_directory_slash: true,
+                classify: true,
+                ..Default::default()
+            },
+            ..create_test_app()
+        };
+        let mut terminal = Terminal::new(TestBackend::new(80, 9)).unwrap();
+
+        terminal
+            .draw(|frame| frame.render_widget(&mut app, frame.area()))
+            .unwrap();
+
+        assert_snapshot!(terminal.backend());
+    }
+
+    #[test]
+    fn renders_quick_jump_hints_only_when_toggled_on() {
+        let mut app = App {
+            config: Config {
+                hide_quick_jump_hints: true,
+                ..Default::default()
+            },
+            ..create_test_app()
+        };
+        let mut terminal = Terminal::new(TestBackend::new(80, 9)).unwrap();
+
+        terminal
+            .draw(|frame| frame.render_widget(&mut app, frame.area()))
+            .unwrap();
+
+        assert_snapshot!("quick_jump_hints_hidden", terminal.backend());
+
+        let _ = app.handle_key_event(KeyCode::Char('H').into(), KeyModifiers::SHIFT);
+
+        terminal
+            .draw(|frame| frame.render_widget(&mut app, frame.area()))
+            .unwrap();
+
+        assert_snapshot!("quick_jump_hints_shown", terminal.backend());
+    }
+
+    #[test]
+    fn renders_parent_context_above_the_list() {
+        let mut app = App {
+            config: Config {
+                show_parent_context: true,
+                ..Default::default()
+            },
+            ..create_test_app()
+        };
+        let mut terminal = Terminal::new(TestBackend::new(80, 10)).unwrap();
+
+        terminal
+            .draw(|frame| frame.render_widget(&mut app, frame.area()))
+            .unwrap();
+
+        assert_snapshot!(terminal.backend());
+    }
+
+    #[test]
+    fn renders_parent_of_entries_with_the_same_name() {
+        let mut app = App::from_entries(
+            ["/home/user/work/notes", "/home/user/personal/notes", "/tmp"]
+                .into_iter()
+                .map(|path| Entry {
+                    path: PathBuf::from(path),
+                    kind: EntryKind::Directory,
+                    name: path.rsplit('/').next().unwrap().into(),
+                    name_lower: path.rsplit('/').next().unwrap().into(),
+                    is_symlink: false,
+                    is_executable: false,
+                    permissions: None,
+                    owner_ids: None,
+                })
+                .collect(),
+            PathBuf::from("/home/user"),
+        );
+        let mut terminal = Terminal::new(TestBackend::new(80, 8)).unwrap();
+
+        terminal
+            .draw(|frame| frame.render_widget(&mut app, frame.area()))
+            .unwrap();
+
+        assert_snapshot!(terminal.backend());
+    }
+
+    #[test]
+    fn renders_correctly_with_help_popup() {
+        let mut app = create_test_app();
+        app.show_help = true;
+
+        let mut terminal = Terminal::new(TestBackend::new(80, 24)).unwrap();
+
+        terminal
+            .draw(|frame| frame.render_widget(&mut app, frame.area()))
+            .unwrap();
+
+        assert_snapshot!(terminal.backend());
+    }
+
+    #[test]
+    fn renders_correctly_with_help_popup_after_key_event() {
+        let mut app = create_test_app();
+        app.handle_key_event(KeyCode::Char('?').into(), KeyModifiers::NONE)
+            .unwrap();
+
+        let mut terminal = Terminal::new(TestBackend::new(80, 24)).unwrap();
+
+        terminal
+            .draw(|frame| frame.render_widget(&mut app, frame.area()))
+            .unwrap();
+
+        assert_snapshot!(terminal.backend());
+    }
+
+    #[test]
+    fn renders_correctly_with_help_popup_scrolled_to_the_end() {
+        let mut app = create_test_app();
+        app.handle_key_event(KeyCode::Char('?').into(), KeyModifiers::NONE)
+            .unwrap();
+        let selected = app.list_state.selected();
+
+        for _ in 0..100 {
+            app.handle_key_event(KeyCode::Char('j').into(), KeyModifiers::NONE)
+                .unwrap();
+        }
+
+        // Scrolling the help doesn't move the selection
+        assert_eq!(app.list_state.selected(), selected);
+
+        let mut terminal = Terminal::new(TestBackend::new(80, 24)).unwrap();
+
+        terminal
+            .draw(|frame| frame.render_widget(&mut app, frame.area()))
+            .unwrap();
+
+        assert_snapshot!(terminal.backend());
+
+        // It stops at the last line, so that scrolling back up takes effect right away
+        let help_scroll = app.help_scroll;
+        app.handle_key_event(KeyCode::Char('k').into(), KeyModifiers::NONE)
+            .unwrap();
+        assert_eq!(app.help_scroll, help_scroll - 1);
+    }
+
+    #[test]
+    fn renders_correctly_without_help_popup_after_key_event_toggle() {
+        let mut app = create_test_app();
+        app.show_help = true;
+        app.handle_key_event(KeyCode::Char('?').into(), KeyModifiers::NONE)
+            .unwrap();
+
+        let mut terminal = Terminal::new(TestBackend::new(80, 24)).unwrap();
+
+        terminal
+            .draw(|frame| frame.render_widget(&mut app, frame.area()))
+            .unwrap();
+
+        assert_snapshot!(terminal.backend());
+    }
+
+    #[test]
+    fn renders_correctly_with_search_input_after_key_events() {
+        let mut app = create_test_app();
+        app.handle_key_event(KeyCode::Char('/').into(), KeyModifiers::NONE)
+            .unwrap();
+        app.handle_key_event(KeyCode::Char('t').into(), KeyModifiers::NONE)
+            .unwrap();
+        app.handle_key_event(KeyCode::Char('e').into(), KeyModifiers::NONE)
+            .unwrap();
+        app.handle_key_event(KeyCode::Char('s').into(), KeyModifiers::NONE)
+            .unwrap();
+        app.handle_key_event(KeyCode::Char('t').into(), KeyModifiers::NONE)
+            .unwrap();
+
+        let mut terminal = Terminal::new(TestBackend::new(80, 9)).unwrap();
+
+        terminal
+            .draw(|frame| frame.render_widget(&mut app, frame.area()))
+            .unwrap();
+
+        assert_snapshot!(terminal.backend());
+    }
+
+    #[test]
+    fn renders_correctly_with_search_input() {
+        let mut app = create_test_app();
+        app.input_mode = InputMode::Search;
+        app.search_input.value = "test".into();
+        app.search_input.index = 4;
+
+        let mut terminal = Terminal::new(TestBackend::new(80, 9)).unwrap();
+
+        terminal
+            .draw(|frame| frame.render_widget(&mut app, frame.area()))
+            .unwrap();
+
+        assert_snapshot!(terminal.backend());
+    }
+
+    #[test]
+    fn first_item_is_preselected_after_render() {
+        let mut app = create_test_app();
+        let mut buffer = Buffer::empty(Rect::new(0, 0, 79, 10));
+
+        assert_eq!(app.list_state.selected(), None);
+
+        app.render(buffer.area, &mut buffer);
+
+        assert_eq!(app.list_state.selected(), Some(0));
+    }
+
+    #[test]
+    fn handle_key_event() {
+        let mut app = create_test_app();
+
+        // Make sure we have 4 items
+        assert_eq!(app.entry_list.len(), 4);
+
+        let _ = app.handle_key_event(KeyCode::Char('q').into(), KeyModifiers::NONE);
+        assert!(app.should_exit);
+
+        let _ = app.handle_key_event(KeyCode::Esc.into(), KeyModifiers::NONE);
+        assert!(app.should_exit);
+
+        let _ = app.handle_key_event(KeyCode::Char('j').into(), KeyModifiers::NONE);
+        assert_eq!(app.list_state.selected(), Some(0));
+
+        let _ = app.handle_key_event(KeyCode::Down.into(), KeyModifiers::NONE);
+        assert_eq!(app.list_state.selected(), Some(1));
+
+        // press down so that we can go back up more than once
+        let _ = app.handle_key_event(KeyCode::Down.into(), KeyModifiers::NONE);
+
+        let _ = app.handle_key_event(KeyCode::Char('k').into(), KeyModifiers::NONE);
+        assert_eq!(app.list_state.selected(), Some(1));
+
+        let _ = app.handle_key_event(KeyCode::Up.into(), KeyModifiers::NONE);
+        assert_eq!(app.list_state.selected(), Some(0));
+
+        let _ = app.handle_key_event(KeyCode::Char('G').into(), KeyModifiers::SHIFT);
+        assert_eq!(app.list_state.selected(), Some(usize::MAX));
+
+        let _ = app.handle_key_event(KeyCode::Char('g').into(), KeyModifiers::NONE);
+        let _ = app.handle_key_event(KeyCode::Char('g').into(), KeyModifiers::NONE);
+        assert_eq!(app.list_state.selected(), Some(0));
+
+        let _ = app.handle_key_event(KeyCode::End.into(), KeyModifiers::NONE);
+        assert_eq!(app.list_state.selected(), Some(usize::MAX));
+
+        let _ = app.handle_key_event(KeyCode::Home.into(), KeyModifiers::NONE);
+        assert_eq!(app.list_state.selected(), Some(0));
+
+        let _ = app.handle_key_event(KeyCode::Char('d').into(), KeyModifiers::CONTROL);
+        assert_eq!(app.list_mode, ListMode::Directory);
+
+        let _ = app.handle_key_event(KeyCode::Char('f').into(), KeyModifiers::CONTROL);
+        assert_eq!(app.list_mode, ListMode::Frecent);
+
+        let _ = app.handle_key_event(KeyCode::Char('d').into(), KeyModifiers::CONTROL);
+        assert_eq!(app.list_mode, ListMode::Directory);
+
+        let _ = app.handle_key_event(KeyCode::Char('?').into(), KeyModifiers::NONE);
+        assert!(app.show_help);
+
+        let _ = app.handle_key_event(KeyCode::Char('/').into(), KeyModifiers::NONE);
+        assert_eq!(app.input_mode, InputMode::Search);
+
+        let _ = app.handle_key_event(KeyCode::Esc.into(), KeyModifiers::NONE);
+        assert_eq!(app.input_mode, InputMode::Normal);
+    }
+
+    #[test]
+    fn search_input_backspace() {
+        let mut app = create_test_app();
+        app.input_mode = InputMode::Search;
+        app.search_input.value = "test".into();
+        app.search_input.index = 4;
+
+        let _ = app.handle_key_event(KeyCode::Backspace.into(), KeyModifiers::NONE);
+        assert_eq!(app.search_input.value, "tes".to_string());
+        assert_eq!(app.search_input.index, 3);
+
+        let _ = app.handle_key_event(KeyCode::Backspace.into(), KeyModifiers::NONE);
+        assert_eq!(app.search_input.value, "te".to_string());
+        assert_eq!(app.search_input.index, 2);
+
+        let _ = app.handle_key_event(KeyCode::Backspace.into(), KeyModifiers::NONE);
+        assert_eq!(app.search_input.value, "t".to_string());
+        assert_eq!(app.search_input.index, 1);
+
+        let _ = app.handle_key_event(KeyCode::Backspace.into(), KeyModifiers::NONE);
+        assert_eq!(app.search_input.value, "".to_string());
+        assert_eq!(app.search_input.index, 0);
+
+        let _ = app.handle_key_event(KeyCode::Backspace.into(), KeyModifiers::NONE);
+        assert_eq!(app.search_input.value, "".to_string());
+        assert_eq!(app.search_input.index, 0);
+    }
+
+    #[test]
+    fn search_input_backspace_with_no_input() {
+        let mut app = create_test_app();
+        app.input_mode = InputMode::Search;
+        app.search_input.value = "".into();
+        app.search_input.index = 0;
+
+        let _ = app.handle_key_event(KeyCode::Backspace.into(), KeyModifiers::NONE);
+        assert_eq!(app.input_mode, InputMode::Normal);
+    }
+
+    #[test]
+    fn search_works_correctly() {
+        let mut app = create_test_app();
+        app.input_mode = InputMode::Search;
+
+        let _ = app.handle_key_event(KeyCode::Char('g').into(), KeyModifiers::NONE);
+        let _ = app.handle_key_event(KeyCode::Char('i').into(), KeyModifiers::NONE);
+        let _ = app.handle_key_event(KeyCode::Char('t').into(), KeyModifiers::NONE);
+
+        assert_eq!(app.search_input.value, "git".to_string());
+        assert_eq!(app.search_input.index, 3);
+
+        app.update_filtered_indices();
+
+        assert_eq!(app.entry_list.filtered_indices, Some(vec![0, 2]));
+    }
+
+    #[test]
+    fn search_renders_correctly() {
+        let mut app = create_test_app();
+        app.input_mode = InputMode::Search;
+
+        let _ = app.handle_key_event(KeyCode::Char('g').into(), KeyModifiers::NONE);
+        let _ = app.handle_key_event(KeyCode::Char('i').into(), KeyModifiers::NONE);
+        let _ = app.handle_key_event(KeyCode::Char('t').into(), KeyModifiers::NONE);
+
+        let mut terminal = Terminal::new(TestBackend::new(80, 9)).unwrap();
+
+        terminal
+            .draw(|frame| frame.render_widget(&mut app, frame.area()))
+            .unwrap();
+
+        assert_snapshot!(terminal.backend());
+    }
+
+    #[test]
+    fn copy_selected_entry_path_copies_absolute_path() {
+        let clipboard = TestClipboard::default();
+        let mut app = App {
+            clipboard: Box::new(clipboard.clone()),
+            ..create_test_app()
+        };
+
+        app.list_state.select(Some(1));
+        let _ = app.handle_key_event(KeyCode::Char('Y').into(), KeyModifiers::SHIFT);
+
+        assert_eq!(*clipboard.text.borrow(), Some("/home/user/dir1/".into()));
+        assert_eq!(app.status_message, Some("Copied '/home/user/dir1/'".into()));
+    }
+
+    #[test]
+    fn copy_selected_entry_relative_path_copies_path_relative_to_current_directory() {
+        let clipboard = TestClipboard::default();
+        let mut app = App {
+            clipboard: Box::new(clipboard.clone()),
+            ..create_test_app()
+        };
+
+        app.list_state.select(Some(1));
+        let _ = app.handle_key_event(KeyCode::Char('y').into(), KeyModifiers::CONTROL);
+
+        assert_eq!(*clipboard.text.borrow(), Some("dir1".into()));
+
+        // The status message is cleared on the next key press
+        let _ = app.handle_key_event(KeyCode::Char('j').into(), KeyModifiers::NONE);
+        assert_eq!(app.status_message, None);
+    }
+
+    #[test]
+    fn copy_selected_entry_uri_copies_file_uri() {
+        let clipboard = TestClipboard::default();
+        let mut app = App {
+            clipboard: Box::new(clipboard.clone()),
+            ..create_test_app()
+        };
+
+        app.list_state.select(Some(3));
+        let _ = app.handle_key_event(KeyCode::Char('U').into(), KeyModifiers::SHIFT);
+
+        assert_eq!(
+            *clipboard.text.borrow(),
+            Some("file:///home/user/Cargo.toml".into())
+        );
+    }
+
+    #[test]
+    fn copy_selected_entry_name_copies_basename() {
+        let clipboard = TestClipboard::default();
+        let mut app = App {
+            clipboard: Box::new(clipboard.clone()),
+            ..create_test_app()
+        };
+
+        app.list_state.select(Some(3));
+        let _ = app.handle_key_event(KeyCode::Char('N').into(), KeyModifiers::SHIFT);
+
+        assert_eq!(*clipboard.text.borrow(), Some("Cargo.toml".into()));
+        assert_eq!(app.status_message, Some("Copied 'Cargo.toml'".into()));
+    }
+
+    #[test]
+    fn copy_selected_entry_name_does_nothing_for_empty_list() {
+        let clipboard = TestClipboard::default();
+        let mut app = App {
+            clipboard: Box::new(clipboard.clone()),
+            ..App::from_entries(Vec::new(), PathBuf::from("/home/user"))
+        };
+
+        let _ = app.handle_key_event(KeyCode::Char('N').into(), KeyModifiers::SHIFT);
+
+        assert_eq!(*clipboard.text.borrow(), None);
+        assert_eq!(app.status_message, None);
+    }
+
+    #[test]
+    fn paste_search_query_filters_by_first_line_of_clipboard() {
+        let clipboard = TestClipboard::default();
+        *clipboard.text.borrow_mut() = Some("  cargo\nsecond line\n".into());
+
+        let mut app = App {
+            clipboard: Box::new(clipboard.clone()),
+            ..create_test_app()
+        };
+
+        let _ = app.handle_key_event(KeyCode::Char('v').into(), KeyModifiers::CONTROL);
+
+        assert_eq!(app.input_mode, InputMode::Search);
+        assert_eq!(app.search_input.value, "cargo");
+        assert_eq!(get_entry_names(&app), vec!["Cargo.toml"]);
+
+        // While searching the clipboard's contents replace the query
+        *clipboard.text.borrow_mut() = Some("git".into());
+        let _ = app.handle_key_event(KeyCode::Char('v').into(), KeyModifiers::CONTROL);
+
+        assert_eq!(app.search_input.value, "git");
+        assert_eq!(get_entry_names(&app), vec![".git", ".gitignore"]);
+    }
+
+    #[test]
+    fn paste_search_query_shows_status_message_when_clipboard_fails() {
+        let mut app = App {
+            clipboard: Box::new(TestClipboard::default()),
+            ..create_test_app()
+        };
+
+        let _ = app.handle_key_event(KeyCode::Char('v').into(), KeyModifiers::CONTROL);
+
+        assert_eq!(app.input_mode, InputMode::Normal);
+        assert_eq!(
+            app.status_message,
+            Some("Failed to read the clipboard: The clipboard is empty".into())
+        );
+    }
+
+    #[test]
+    fn exit_confirmation_shows_returned_path() {
+        let mut app = App {
+            config: Config {
+                exit_confirmation_duration: Some(Duration::from_millis(1)),
+                ..Default::default()
+            },
+            ..create_test_app()
+        };
+
+        let mut terminal = Terminal::new(TestBackend::new(80, 9)).unwrap();
+
+        let _ = app.handle_key_event(KeyCode::Char('q').into(), KeyModifiers::NONE);
+        let result = app.run(&mut terminal).unwrap();
+
+        let RunOutcome::ChangedTo(path) = result else {
+            panic!("Expected the app to return a path, got {result:?}");
+        };
+
+        assert_eq!(
+            app.status_message,
+            Some(format!("Exiting to '{}'", path.display()))
+        );
+        assert_snapshot!(terminal.backend());
+    }
+
+    #[test]
+    fn exit_returns_current_directory() {
+        let mut app = create_test_app();
+        let mut terminal = Terminal::new(TestBackend::new(80, 9)).unwrap();
+
+        let _ = app.handle_key_event(KeyCode::Char('q').into(), KeyModifiers::NONE);
+
+        assert_eq!(
+            app.run(&mut terminal).unwrap(),
+            RunOutcome::ChangedTo(PathBuf::from("/home/user"))
+        );
+    }
+
+    #[test]
+    fn exit_without_changing_directory_returns_cancelled() {
+        let mut app = create_test_app();
+        let mut terminal = Terminal::new(TestBackend::new(80, 9)).unwrap();
+
+        let _ = app.handle_key_event(KeyCode::Char('q').into(), KeyModifiers::CONTROL);
+
+        assert_eq!(app.run(&mut terminal).unwrap(), RunOutcome::Cancelled);
+    }
+
+    #[test]
+    fn renders_correctly_with_max_name_width() {
+        let mut app = App {
+            config: Config {
+                max_name_width: Some(16),
+                ..Default::default()
+            },
+            ..create_test_app()
+        };
+
+        app.entry_list.items.insert(
+            0,
+            Entry {
+                path: PathBuf::from("/home/user/a_directory_with_a_very_long_name/"),
+                kind: EntryKind::Directory,
+                name: "a_directory_with_a_very_long_name".into(),
+                name_lower: "a_directory_with_a_very_long_name".into(),
+                is_symlink: false,
+                is_executable: false,
+                permissions: None,
+                owner_ids: None,
+            },
+        );
+
+        let mut terminal = Terminal::new(TestBackend::new(80, 10)).unwrap();
+
+        terminal
+            .draw(|frame| frame.render_widget(&mut app, frame.area()))
+            .unwrap();
+
+        assert_snapshot!(terminal.backend());
+    }
+
+    #[test]
+    fn change_directory_reports_the_number_of_items() {
+        let temp_dir = tempfile::tempdir().unwrap();
+
+        std::fs::create_dir(temp_dir.path().join("src")).unwrap();
+        File::create(temp_dir.path().join("Cargo.toml")).unwrap();
+        File::create(temp_dir.path().join(".hidden")).unwrap();
+
+        let mut app = App::default();
+        let result = app.change_directory(temp_dir.path()).unwrap();
+
+        assert_eq!(
+            result,
+            ChangeResult {
+                items_count: 3,
+                unreadable_count: 0,
+            }
+        );
+        assert_eq!(app.status_message, None);
+
+        app.config.hide_hidden_files = true;
+        let result = app.change_directory(temp_dir.path()).unwrap();
+
+        assert_eq!(result.items_count, 2);
+    }
+
+    #[test]
+    fn change_directory_sets_terminal_title_when_enabled() {
+        let temp_dir = tempfile::tempdir().unwrap();
+
+        let mut app = App::default();
+        app.change_directory(temp_dir.path()).unwrap();
+        assert_eq!(app.pending_terminal_title, None);
+
+        let mut app = App::new(Config {
+            set_terminal_title: true,
+            ..Default::default()
+        });
+        app.change_directory(temp_dir.path()).unwrap();
+
+        assert_eq!(
+            app.pending_terminal_title,
+            Some(temp_dir.path().display().to_string())
+        );
+    }
+
+    #[test]
+    fn entering_large_directory_asks_for_confirmation() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let large_dir = temp_dir.path().join("large_dir");
+        std::fs::create_dir(&large_dir).unwrap();
+
+        for i in 0..5 {
+            std::fs::File::create(large_dir.join(format!("file_{i}.txt"))).unwrap();
+        }
+
+        let mut app = App::new(Config {
+            large_directory_threshold: Some(3),
+            ..Default::default()
+        });
+        app.change_directory(temp_dir.path()).unwrap();
+
+        // Declining keeps us where we are
+        let _ = app.handle_key_event(KeyCode::Enter.into(), KeyModifiers::NONE);
+        assert_eq!(
+            app.pending_confirmation,
+            Some(Confirmation::EnterLargeDirectory {
+                path: large_dir.clone(),
+                entries_count: 5
+            })
+        );
+        assert_eq!(app.current_directory, temp_dir.path());
+
+        let _ = app.handle_key_event(KeyCode::Char('n').into(), KeyModifiers::NONE);
+        assert_eq!(app.pending_confirmation, None);
+        assert_eq!(app.current_directory, temp_dir.path());
+
+        // Accepting enters the directory
+        let _ = app.handle_key_event(KeyCode::Enter.into(), KeyModifiers::NONE);
+        assert!(app.pending_confirmation.is_some());
+
+        let _ = app.handle_key_event(KeyCode::Char('y').into(), KeyModifiers::NONE);
+        assert_eq!(app.pending_confirmation, None);
+        assert_eq!(app.current_directory, large_dir);
+    }
+
+    #[test]
+    fn entering_directory_under_threshold_does_not_ask_for_confirmation() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let small_dir = temp_dir.path().join("small_dir");
+        std::fs::create_dir(&small_dir).unwrap();
+        std::fs::File::create(small_dir.join("file.txt")).unwrap();
+
+        let mut app = App::new(Config {
+            large_directory_threshold: Some(3),
+            ..Default::default()
+        });
+        app.change_directory(temp_dir.path()).unwrap();
+
+        let _ = app.handle_key_event(KeyCode::Enter.into(), KeyModifiers::NONE);
+
+        assert_eq!(app.pending_confirmation, None);
+        assert_eq!(app.current_directory, small_dir);
+    }
+
+    #[test]
+    fn renders_correctly_with_confirmation_popup() {
+        let mut app = create_test_app();
+        app.pending_confirmation = Some(Confirmation::EnterLargeDirectory {
+            path: PathBuf::from("/home/user/dir1/"),
+            entries_count: 100000,
+        });
+
+        let mut terminal = Terminal::new(TestBackend::new(80, 12)).unwrap();
+
+        terminal
+            .draw(|frame| frame.render_widget(&mut app, frame.area()))
+            .unwrap();
+
+        assert_snapshot!(terminal.backend());
+    }
+
+    #[test]
+    fn app_exits_to_current_directory_after_idle_timeout() {
+        let mut app = App {
+            config: Config {
+                idle_timeout: Some(Duration::from_secs(60)),
+                ..Default::default()
+            },
+            ..create_test_app()
+        };
+
+        let now = Instant::now();
+        app.last_input_time = Some(now);
+
+        app.exit_if_idle(now + Duration::from_secs(59));
+        assert!(!app.should_exit);
+
+        app.exit_if_idle(now + Duration::from_secs(60));
+        assert!(app.should_exit);
+
+        let mut terminal = Terminal::new(TestBackend::new(80, 9)).unwrap();
+        assert_eq!(
+            app.run(&mut terminal).unwrap(),
+            RunOutcome::ChangedTo(PathBuf::from("/home/user"))
+        );
+    }
+
+    #[test]
+    fn app_does_not_exit_when_idle_without_idle_timeout() {
+        let mut app = create_test_app();
+
+        let now = Instant::now();
+        app.last_input_time = Some(now);
+        app.exit_if_idle(now + Duration::from_secs(24 * 60 * 60));
+
+        assert!(!app.should_exit);
+    }
+
+    #[test]
+    fn confirmation_is_cancelled_after_timeout() {
+        let mut app = App {
+            config: Config {
+                confirmation_timeout: Some(Duration::from_secs(10)),
+                ..Default::default()
+            },
+            ..create_test_app()
+        };
+
+        app.ask_for_confirmation(Confirmation::EnterLargeDirectory {
+            path: PathBuf::from("/home/user/dir1/"),
+            entries_count: 100000,
+        });
+
+        let mut terminal = Terminal::new(TestBackend::new(80, 12)).unwrap();
+        terminal
+            .draw(|frame| frame.render_widget(&mut app, frame.area()))
+            .unwrap();
+        assert_snapshot!(terminal.backend());
+
+        // There's still time to answer
+        let asked_at = app.confirmation_time.unwrap();
+        app.cancel_expired_confirmation(asked_at + Duration::from_secs(7));
+        assert!(app.pending_confirmation.is_some());
+
+        app.cancel_expired_confirmation(asked_at + Duration::from_secs(10));
+
+        assert!(app.pending_confirmation.is_none());
+        assert_eq!(app.current_directory, PathBuf::from("/home/user"));
+        assert_eq!(
+            app.status_message,
+            Some("The confirmation has timed out".into())
+        );
+    }
+
+    #[test]
+    fn open_shell_in_selected_entry_returns_selected_directory() {
+        let mut app = create_test_app();
+        let mut terminal = Terminal::new(TestBackend::new(80, 9)).unwrap();
+
+        app.list_state.select(Some(1));
+        let _ = app.handle_key_event(KeyCode::Char('T').into(), KeyModifiers::SHIFT);
+
+        assert!(app.should_exit);
+        assert_eq!(
+            app.run(&mut terminal).unwrap(),
+            RunOutcome::OpenShellIn(PathBuf::from("/home/user/dir1/"))
+        );
+    }
+
+    #[test]
+    fn open_shell_with_selected_file_returns_current_directory() {
+        let mut app = create_test_app();
+
+        app.list_state.select(Some(3));
+        let _ = app.handle_key_event(KeyCode::Char('T').into(), KeyModifiers::SHIFT);
+
+        assert_eq!(
+            app.exit_outcome,
+            Some(RunOutcome::OpenShellIn(PathBuf::from("/home/user")))
+        );
+    }
+
+    #[test]
+    fn enter_selected_entry_and_exit_returns_selected_directory() {
+        let mut app = create_test_app();
+        let mut terminal = Terminal::new(TestBackend::new(80, 9)).unwrap();
+
+        app.list_state.select(Some(1));
+        let _ = app.handle_key_event(KeyCode::Char('L').into(), KeyModifiers::SHIFT);
+
+        assert!(app.should_exit);
+        assert_eq!(
+            app.run(&mut terminal).unwrap(),
+            RunOutcome::ChangedTo(PathBuf::from("/home/user/dir1/"))
+        );
+    }
+
+    #[test]
+    fn enter_selected_entry_and_exit_returns_selected_file() {
+        let mut app = create_test_app();
+        let mut terminal = Terminal::new(TestBackend::new(80, 9)).unwrap();
+
+        app.list_state.select(Some(3));
+        let _ = app.handle_key_event(KeyCode::Char('L').into(), KeyModifiers::SHIFT);
+
+        assert_eq!(
+            app.run(&mut terminal).unwrap(),
+            RunOutcome::SelectedFile(PathBuf::from("/home/user/Cargo.toml"))
+        );
+    }
+
+    #[test]
+    fn enter_selected_entry_and_exit_clamps_selection_past_the_last_entry() {
+        let mut app = create_test_app();
+        let mut terminal = Terminal::new(TestBackend::new(80, 9)).unwrap();
+
+        // The last entry is only resolved when rendering, so the selection is out of range here
+        let _ = app.handle_key_event(KeyCode::End.into(), KeyModifiers::NONE);
+        let _ = app.handle_key_event(KeyCode::Char('L').into(), KeyModifiers::SHIFT);
+
+        assert_eq!(
+            app.run(&mut terminal).unwrap(),
+            RunOutcome::SelectedFile(PathBuf::from("/home/user/Cargo.toml"))
+        );
+    }
+
+    #[test]
+    fn change_directory_to_selected_entry_clamps_selection_to_filtered_entries() {
+        let mut app = create_test_app();
+
+        // Only ".git" and ".gitignore" match, the selection moves past both of them
+        type_search_query(&mut app, "git");
+        for _ in 0..5 {
+            let _ = app.handle_key_event(KeyCode::Down.into(), KeyModifiers::NONE);
+        }
+        assert_eq!(app.list_state.selected(), Some(4));
+
+        let _ = app.handle_key_event(KeyCode::Enter.into(), KeyModifiers::NONE);
+
+        assert!(app.should_exit);
+        assert_eq!(
+            app.get_selected_entry().map(|x| x.path.clone()),
+            Some(PathBuf::from("/home/user/.gitignore"))
+        );
+    }
+
+    #[test]
+    fn renders_correctly_with_command_palette() {
+        let mut app = create_test_app();
+        let mut terminal = Terminal::new(TestBackend::new(80, 24)).unwrap();
+
+        let _ = app.handle_key_event(KeyCode::Char(':').into(), KeyModifiers::NONE);
+        for c in "toggle".chars() {
+            let _ = app.handle_key_event(KeyCode::Char(c).into(), KeyModifiers::NONE);
+        }
+        let _ = app.handle_key_event(KeyCode::Down.into(), KeyModifiers::NONE);
+
+        terminal
+            .draw(|frame| frame.render_widget(&mut app, frame.area()))
+            .unwrap();
+
+        assert_snapshot!(terminal.backend());
+    }
+
+    #[test]
+    fn command_palette_executes_selected_action() {
+        let mut app = create_test_app();
+
+        let _ = app.handle_key_event(KeyCode::Char(':').into(), KeyModifiers::NONE);
+        assert!(app.command_palette.is_some());
+
+        // The typed keys go to the palette instead of triggering their own actions
+        for c in "help".chars() {
+            let _ = app.handle_key_event(KeyCode::Char(c).into(), KeyModifiers::NONE);
+        }
+        assert!(!app.should_exit);
+
+        let _ = app.handle_key_event(KeyCode::Enter.into(), KeyModifiers::NONE);
+
+        assert!(app.command_palette.is_none());
+        assert!(app.show_help);
+
+        let _ = app.handle_key_event(KeyCode::Char(':').into(), KeyModifiers::NONE);
+        let _ = app.handle_key_event(KeyCode::Esc.into(), KeyModifiers::NONE);
+
+        assert!(app.command_palette.is_none());
+        assert!(!app.should_exit);
+    }
+
+    #[test]
+    fn command_palette_can_be_opened_with_configured_key() {
+        let mut app = App::new(Config {
+            command_palette_key: Some(';'),
+            ..Default::default()
+        });
+
+        let _ = app.handle_key_event(KeyCode::Char(';').into(), KeyModifiers::NONE);
+
+        assert!(app.command_palette.is_some());
+    }
+
+    #[test]
+    fn accept_search_input_keeps_filtered_entries_and_selection() {
+        let mut app = create_test_app();
+
+        type_search_query(&mut app, "git");
+        let _ = app.handle_key_event(KeyCode::Down.into(), KeyModifiers::NONE);
+        let _ = app.handle_key_event(KeyCode::Down.into(), KeyModifiers::NONE);
+        let _ = app.handle_key_event(KeyCode::Esc.into(), KeyModifiers::NONE);
+
+        assert_eq!(app.input_mode, InputMode::Normal);
+        assert_eq!(app.search_input.value, "git");
+        assert_eq!(get_entry_names(&app), vec![".git", ".gitignore"]);
+        assert_eq!(app.list_state.selected(), Some(1));
+    }
+
+    #[test]
+    fn enter_in_search_mode_commits_query_and_enters_selected_entry() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir(temp_dir.path().join("alpha")).unwrap();
+        std::fs::create_dir(temp_dir.path().join("beta")).unwrap();
+
+        let mut app = App::default();
+        app.change_directory(temp_dir.path()).unwrap();
+
+        type_search_query(&mut app, "bet");
+        let _ = app.handle_key_event(KeyCode::Enter.into(), KeyModifiers::NONE);
+
+        assert_eq!(app.input_mode, InputMode::Normal);
+        assert_eq!(app.search_input.value, "");
+        assert_eq!(app.current_directory, temp_dir.path().join("beta"));
+        assert_eq!(app.search_history.entries, vec!["bet"]);
+    }
+
+    #[test]
+    fn search_persists_across_directory_changes_when_configured() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir(temp_dir.path().join("app")).unwrap();
+        File::create(temp_dir.path().join("app.txt")).unwrap();
+        File::create(temp_dir.path().join("app").join("app.rs")).unwrap();
+        File::create(temp_dir.path().join("app").join("readme.md")).unwrap();
+
+        let mut app = App::new(Config {
+            persist_search_on_cd: true,
+            ..Default::default()
+        });
+        app.change_directory(temp_dir.path()).unwrap();
+
+        type_search_query(&mut app, "app");
+        assert_eq!(get_entry_names(&app), vec!["app", "app.txt"]);
+
+        let _ = app.handle_key_event(KeyCode::Enter.into(), KeyModifiers::NONE);
+
+        assert_eq!(app.current_directory, temp_dir.path().join("app"));
+        assert_eq!(app.search_input.value, "app");
+        assert_eq!(get_entry_names(&app), vec!["app.rs"]);
+
+        // Going back up re-applies the query as well
+        let _ = app.handle_key_event(KeyCode::Char('h').into(), KeyModifiers::NONE);
+
+        assert_eq!(app.current_directory, temp_dir.path());
+        assert_eq!(get_entry_names(&app), vec!["app", "app.txt"]);
+    }
+
+    #[test]
+    fn cancel_search_input_restores_full_listing_keeping_selected_entry() {
+        let mut app = create_test_app();
+
+        type_search_query(&mut app, "git");
+        let _ = app.handle_key_event(KeyCode::Down.into(), KeyModifiers::NONE);
+        let _ = app.handle_key_event(KeyCode::Down.into(), KeyModifiers::NONE);
+        let _ = app.handle_key_event(KeyCode::Char('c').into(), KeyModifiers::CONTROL);
+
+        assert_eq!(app.input_mode, InputMode::Normal);
+        assert_eq!(app.search_input.value, "");
+        assert_eq!(
+            get_entry_names(&app),
+            vec![".git", "dir1", ".gitignore", "Cargo.toml"]
+        );
+        assert_eq!(app.list_state.selected(), Some(2));
+
+        // A cancelled query isn't added to the history
+        assert!(app.search_history.entries.is_empty());
+    }
+
+    fn create_grid_test_app() -> App {
+        let mut entries: Vec<Entry> = ["bin", "etc", "lib"]
+            .into_iter()
+            .map(|x| Entry {
+                path: PathBuf::from(format!("/home/user/{x}/")),
+                kind: EntryKind::Directory,
+                name: x.into(),
+                name_lower: x.into(),
+                is_symlink: false,
+                is_executable: false,
+                permissions: None,
+                owner_ids: None,
+            })
+            .collect();
+
+        entries.extend((1..=12).map(|i| Entry {
+            path: PathBuf::from(format!("/home/user/f{i:02}.txt")),
+            kind: EntryKind::File {
+                extension: Some("txt".into()),
+            },
+            name: format!("f{i:02}.txt"),
+            name_lower: format!("f{i:02}.txt"),
+            is_symlink: false,
+            is_executable: false,
+            permissions: None,
+            owner_ids: None,
+        }));
+
+        App {
+            config: Config {
+                grid: true,
+                ..Default::default()
+            },
+            ..App::from_entries(entries, PathBuf::from("/home/user"))
+        }
+    }
+
+    #[test]
+    fn renders_correctly_in_grid() {
+        let mut app = create_grid_test_app();
+        let mut terminal = Terminal::new(TestBackend::new(60, 9)).unwrap();
+
+        terminal
+            .draw(|frame| frame.render_widget(&mut app, frame.area()))
+            .unwrap();
+
+        assert_snapshot!(terminal.backend());
+    }
+
+    #[test]
+    fn select_next_column_moves_within_grid() {
+        let mut app = create_grid_test_app();
+        let mut terminal = Terminal::new(TestBackend::new(60, 9)).unwrap();
+
+        // The columns are only known once the grid has been rendered
+        let _ = app.handle_key_event(KeyCode::Tab.into(), KeyModifiers::NONE);
+        assert_eq!(app.list_state.selected(), None);
+
+        terminal
+            .draw(|frame| frame.render_widget(&mut app, frame.area()))
+            .unwrap();
+        let rows = app.grid_rows.unwrap();
+
+        let _ = app.handle_key_event(KeyCode::Tab.into(), KeyModifiers::NONE);
+        assert_eq!(app.list_state.selected(), Some(rows));
+
+        // The last column can be shorter, so we stop at the last entry
+        for _ in 0..5 {
+            let _ = app.handle_key_event(KeyCode::Tab.into(), KeyModifiers::NONE);
+        }
+        assert_eq!(app.list_state.selected(), Some(14));
+
+        let _ = app.handle_key_event(KeyCode::BackTab.into(), KeyModifiers::SHIFT);
+        assert_eq!(app.list_state.selected(), Some(14 - rows));
+    }
+
+    #[test]
+    fn slow_path_entries_render_placeholders_until_metadata_is_read() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        File::create(temp_dir.path().join("notes.txt")).unwrap();
+
+        let mut app = App {
+            config: Config {
+                slow_path_prefixes: vec![temp_dir.path().to_path_buf()],
+                permissions_format: Some(PermissionsFormat::Symbolic),
+                ..Default::default()
+            },
+            metadata_reader: |_| Some((0o100755, (0, 0))),
+            ..Default::default()
+        };
+        app.change_directory(temp_dir.path()).unwrap();
+
+        let mut terminal = Terminal::new(TestBackend::new(60, 6)).unwrap();
+        terminal
+            .draw(|frame| frame.render_widget(&mut app, frame.area()))
+            .unwrap();
+
+        let rendered = terminal.backend().to_string();
+        assert!(rendered.contains(&format!("…{}notes.txt", " ".repeat(9))));
+        assert!(!rendered.contains("rwx"));
+
+        let start = Instant::now();
+        while app.metadata_loader.is_some() && start.elapsed() < Duration::from_secs(5) {
+            app.receive_metadata();
+        }
+
+        terminal
+            .draw(|frame| frame.render_widget(&mut app, frame.area()))
+            .unwrap();
+
+        assert!(terminal
+            .backend()
+            .to_string()
+            .contains("rwxr-xr-x notes.txt"));
+        assert!(app.entry_list.items[0].is_executable);
+    }
+
+    #[test]
+    fn renders_correctly_with_custom_search_prompt() {
+        let mut app = App {
+            config: Config {
+                theme: Theme {
+                    search_prompt: "🔍 ".into(),
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+            ..create_test_app()
+        };
+        let mut terminal = Terminal::new(TestBackend::new(80, 9)).unwrap();
+
+        type_search_query(&mut app, "ca");
+
+        terminal
+            .draw(|frame| frame.render_widget(&mut app, frame.area()))
+            .unwrap();
+
+        assert_snapshot!(terminal.backend());
+
+        // The space, the prompt (an emoji is two columns wide, followed by a space) and the query
+        assert_eq!(app.cursor_position, Some((6, 8)));
+    }
+
+    #[test]
+    fn search_history_recalls_queries_in_order() {
+        let mut search_history = SearchHistory::default();
+        search_history.push("first");
+        search_history.push("second");
+        search_history.push("third");
+
+        assert_eq!(search_history.recall_previous(), Some("third"));
+        assert_eq!(search_history.recall_previous(), Some("second"));
+        assert_eq!(search_history.recall_previous(), Some("first"));
+        assert_eq!(search_history.recall_previous(), Some("first"));
+        assert_eq!(search_history.recall_next(), Some("second"));
+        assert_eq!(search_history.recall_next(), Some("third"));
+        assert_eq!(search_history.recall_next(), None);
+        assert_eq!(search_history.recall_next(), None);
+    }
+
+    #[test]
+    fn search_history_deduplicates_queries() {
+        let mut search_history = SearchHistory::default();
+        search_history.push("first");
+        search_history.push("second");
+        search_history.push("first");
+        search_history.push("");
+
+        assert_eq!(search_history.entries, vec!["second", "first"]);
+    }
+
+    #[test]
+    fn search_history_is_recalled_in_search_mode() {
+        let mut app = create_test_app();
+
+        for query in ["git", "dir"] {
+            let _ = app.handle_key_event(KeyCode::Char('/').into(), KeyModifiers::NONE);
+            for c in query.chars() {
+                let _ = app.handle_key_event(KeyCode::Char(c).into(), KeyModifiers::NONE);
+            }
+            let _ = app.handle_key_event(KeyCode::Esc.into(), KeyModifiers::NONE);
+        }
+
+        let _ = app.handle_key_event(KeyCode::Char('/').into(), KeyModifiers::NONE);
+
+        let _ = app.handle_key_event(KeyCode::Char('p').into(), KeyModifiers::CONTROL);
+        assert_eq!(app.search_input.value, "dir");
+        assert_eq!(app.search_input.index, 3);
+        assert_eq!(app.entry_list.filtered_indices, Some(vec![1]));
+
+        let _ = app.handle_key_event(KeyCode::Char('p').into(), KeyModifiers::CONTROL);
+        assert_eq!(app.search_input.value, "git");
+
+        // Editing a recalled query and accepting it adds a new entry
+        let _ = app.handle_key_event(KeyCode::Char('i').into(), KeyModifiers::NONE);
+        let _ = app.handle_key_event(KeyCode::Esc.into(), KeyModifiers::NONE);
+
+        assert_eq!(app.search_history.entries, vec!["git", "dir", "giti"]);
+    }
+
+    #[test]
+    fn push_current_directory_to_stack_writes_stack_file() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let file_path = temp_dir.path().join("stack");
+
+        let mut app = App {
+            config: Config {
+                directory_stack_file: Some(file_path.clone()),
+                ..Default::default()
+            },
+            ..create_test_app()
+        };
+
+        let _ = app.handle_key_event(KeyCode::Char('M').into(), KeyModifiers::SHIFT);
+
+        let mut stack = DirectoryStack::load_from_disk(&file_path).unwrap();
+        assert_eq!(stack.pop().unwrap(), Some(PathBuf::from("/home/user")));
+    }
+
+    #[test]
+    fn pinned_entry_sorts_above_the_others_in_later_listings() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        for name in ["alpha", "beta", "zeta"] {
+            std::fs::create_dir(temp_dir.path().join(name)).unwrap();
+        }
+        let data_dir = tempfile::tempdir().unwrap();
+        let pins_file = data_dir.path().join("pins");
+
+        let create_app = || {
+            let mut app = App::new(Config {
+                pins_file: Some(pins_file.clone()),
+                ..Default::default()
+            });
+            app.change_directory(temp_dir.path()).unwrap();
+            app
+        };
+        let get_names = |app: &App| {
+            app.entry_list
+                .get_filtered_entries()
+                .iter()
+                .map(|x| x.name.clone())
+                .collect::<Vec<_>>()
+        };
+
+        let mut app = create_app();
+        app.list_state.select(Some(2));
+        let _ = app.handle_key_event(KeyCode::Char('*').into(), KeyModifiers::NONE);
+
+        assert_eq!(get_names(&app), ["zeta", "alpha", "beta"]);
+        assert_eq!(app.get_selected_entry().unwrap().name, "zeta");
+
+        // The pin is persisted, so it applies to the next session as well
+        let mut app = create_app();
+        assert_eq!(get_names(&app), ["zeta", "alpha", "beta"]);
+
+        app.list_state.select(Some(0));
+        let _ = app.handle_key_event(KeyCode::Char('*').into(), KeyModifiers::NONE);
+
+        assert_eq!(get_names(&app), ["alpha", "beta", "zeta"]);
+    }
+
+    #[test]
+    fn favorite_slots_are_set_jumped_to_and_persisted() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let projects = temp_dir.path().join("projects");
+        std::fs::create_dir(&projects).unwrap();
+        let data_dir = tempfile::tempdir().unwrap();
+        let favorites_file = data_dir.path().join("favorites");
+
+        let create_app = || {
+            let mut app = App::new(Config {
+                favorites_file: Some(favorites_file.clone()),
+                ..Default::default()
+            });
+            app.change_directory(temp_dir.path()).unwrap();
+            app
+        };
+        let press_keys = |app: &mut App, keys: &[(char, KeyModifiers)]| {
+            for &(c, modifiers) in keys {
+                app.handle_key_event(KeyCode::Char(c).into(), modifiers)
+                    .unwrap();
+            }
+        };
+
+        let mut app = create_app();
+        app.change_directory(&projects).unwrap();
+        press_keys(
+            &mut app,
+            &[('s', KeyModifiers::CONTROL), ('2', KeyModifiers::NONE)],
+        );
+
+        assert_eq!(
+            app.status_message,
+            Some("Saved the directory to slot 2".into())
+        );
+
+        app.change_directory(temp_dir.path()).unwrap();
+        press_keys(
+            &mut app,
+            &[('\'', KeyModifiers::NONE), ('2', KeyModifiers::NONE)],
+        );
+        assert_eq!(app.current_directory, projects);
+
+        // An unset slot leaves the directory as is
+        press_keys(
+            &mut app,
+            &[('\'', KeyModifiers::NONE), ('5', KeyModifiers::NONE)],
+        );
+        assert_eq!(app.current_directory, projects);
+        assert_eq!(app.status_message, Some("Slot 5 is empty".into()));
+
+        // The slots are persisted, so they're there in the next session as well
+        let mut app = create_app();
+        press_keys(
+            &mut app,
+            &[('\'', KeyModifiers::NONE), ('2', KeyModifiers::NONE)],
+        );
+        assert_eq!(app.current_directory, projects);
+    }
+
+    #[test]
+    fn compute_selected_directory_size_shows_total_size_of_the_tree() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let nested = temp_dir.path().join("data").join("nested");
+        std::fs::create_dir_all(&nested).unwrap();
+        std::fs::write(temp_dir.path().join("data").join("one"), [0; 1024]).unwrap();
+        std::fs::write(nested.join("two"), [0; 2048]).unwrap();
+        std::fs::write(temp_dir.path().join("outside"), [0; 100]).unwrap();
+
+        let mut app = App::default();
+        app.change_directory(temp_dir.path()).unwrap();
+        app.list_state.select(Some(0));
+
+        let _ = app.handle_key_event(KeyCode::Char('D').into(), KeyModifiers::SHIFT);
+        assert!(app.directory_size.is_some());
+
+        let start = Instant::now();
+        while app.directory_size.is_some() && start.elapsed() < Duration::from_secs(5) {
+            app.receive_directory_size();
+        }
+
+        assert_eq!(
+            app.status_message,
+            Some(format!(
+                "'{}' takes 3.0 KiB in 2 files",
+                temp_dir.path().join("data").display()
+            ))
+        );
+    }
+
+    #[test]
+    fn compute_selected_directory_size_is_cancelled_with_escape() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir(temp_dir.path().join("data")).unwrap();
+
+        let mut app = App::default();
+        app.change_directory(temp_dir.path()).unwrap();
+        app.list_state.select(Some(0));
+
+        let _ = app.handle_key_event(KeyCode::Char('D').into(), KeyModifiers::SHIFT);
+        let _ = app.handle_key_event(KeyCode::Esc.into(), KeyModifiers::NONE);
+
+        assert!(app.directory_size.is_none());
+        assert!(!app.should_exit);
+    }
+
+    #[test]
+    fn toggle_git_ignored_only_lists_only_ignored_entries() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let repository = temp_dir.path();
+
+        std::fs::create_dir(repository.join(".git")).unwrap();
+        std::fs::create_dir(repository.join("target")).unwrap();
+        std::fs::create_dir(repository.join("src")).unwrap();
+        std::fs::write(repository.join(".gitignore"), "*.log\ntarget/\n").unwrap();
+        std::fs::write(repository.join("build.log"), "").unwrap();
+        std::fs::write(repository.join("main.rs"), "").unwrap();
+
+        let mut app = App::default();
+        app.change_directory(repository).unwrap();
+
+        let _ = app.handle_key_event(KeyCode::Char('I').into(), KeyModifiers::SHIFT);
+        assert_eq!(get_entry_names(&app), vec!["target", "build.log"]);
+
+        let _ = app.handle_key_event(KeyCode::Char('I').into(), KeyModifiers::SHIFT);
+        assert_eq!(
+            get_entry_names(&app),
+            vec![
+                ".git",
+                "src",
+                "target",
+                ".gitignore",
+                "build.log",
+                "main.rs"
+            ]
+        );
+    }
+
+    #[test]
+    fn toggle_git_ignored_only_does_nothing_outside_of_a_repository() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        std::fs::write(temp_dir.path().join("build.log"), "").unwrap();
+
+        let mut app = App::default();
+        app.change_directory(temp_dir.path()).unwrap();
+
+        let _ = app.handle_key_event(KeyCode::Char('I').into(), KeyModifiers::SHIFT);
+
+        assert!(!app.show_only_git_ignored);
+        assert_eq!(get_entry_names(&app), vec!["build.log"]);
+        assert_eq!(
+            app.status_message,
+            Some("Not inside a git repository".into())
+        );
+    }
+
+    #[test]
+    fn change_directory_appends_a_line_to_the_navigation_log() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir(temp_dir.path().join("sub")).unwrap();
+        let log_file = temp_dir.path().join("navigation.log");
+
+        let mut app = App::new(Config {
+            navigation_log_file: Some(log_file.clone()),
+            ..Default::default()
+        });
+        app.change_directory(temp_dir.path()).unwrap();
+        app.change_directory(temp_dir.path().join("sub")).unwrap();
+        app.navigation_log.as_mut().unwrap().flush().unwrap();
+
+        let contents = std::fs::read_to_string(&log_file).unwrap();
+        let lines: Vec<_> = contents
+            .lines()
+            .map(|x| x.split_once('\t').unwrap())
+            .collect();
+
+        assert_eq!(lines.len(), 2);
+        assert!(lines
+            .iter()
+            .all(|(timestamp, _)| timestamp.parse::<u64>().is_ok()));
+        assert_eq!(lines[0].1, temp_dir.path().display().to_string());
+        assert_eq!(
+            lines[1].1,
+            temp_dir.path().join("sub").display().to_string()
+        );
+    }
+
+    #[test]
+    fn directory_config_applies_only_within_its_directory() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let configured = temp_dir.path().join("configured");
+
+        for directory in [temp_dir.path(), configured.as_path()] {
+            for name in ["alpha", "beta", "target"] {
+                std::fs::create_dir_all(directory.join(name)).unwrap();
+            }
+        }
+        std::fs::write(
+            configured.join(".tiny-fe.toml"),
+            "sort-descending = true\nhide-hidden = true\nignore = [\"target\"]\n",
+        )
+        .unwrap();
+
+        let mut app = App::default();
+        app.change_directory(&configured).unwrap();
+        assert_eq!(get_entry_names(&app), vec!["beta", "alpha"]);
+
+        // Toggling a setting still works within the directory
+        let _ = app.handle_key_event(KeyCode::Char('O').into(), KeyModifiers::SHIFT);
+        assert_eq!(get_entry_names(&app), vec!["alpha", "beta"]);
+
+        app.change_directory(temp_dir.path()).unwrap();
+        assert_eq!(
+            get_entry_names(&app),
+            vec!["alpha", "beta", "configured", "target"]
+        );
+        assert!(!app.config.sort_descending);
+        assert!(!app.config.hide_hidden_files);
+    }
+
+    #[test]
+    fn change_directory_spawns_on_cd_command_with_new_path() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let spawner = TestCommandSpawner::default();
+
+        let mut app = App {
+            config: Config {
+                on_cd_command: Some("logger -t tiny-fe".into()),
+                ..Default::default()
+            },
+            command_spawner: Box::new(spawner.clone()),
+            ..Default::default()
+        };
+
+        app.change_directory(temp_dir.path()).unwrap();
+
+        assert_eq!(
+            *spawner.spawned.borrow(),
+            vec![(
+                "logger -t tiny-fe".to_string(),
+                temp_dir.path().to_path_buf()
+            )]
+        );
+        assert_eq!(app.status_message, None);
+    }
+
+    #[test]
+    fn change_directory_does_not_spawn_without_on_cd_command() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let spawner = TestCommandSpawner::default();
+
+        let mut app = App {
+            command_spawner: Box::new(spawner.clone()),
+            ..Default::default()
+        };
+
+        app.change_directory(temp_dir.path()).unwrap();
+
+        assert!(spawner.spawned.borrow().is_empty());
+    }
+
+    #[test]
+    fn change_directory_shows_status_message_when_on_cd_command_fails() {
+        let temp_dir = tempfile::tempdir().unwrap();
+
+        let mut app = App {
+            config: Config {
+                on_cd_command: Some("missing".into()),
+                ..Default::default()
+            },
+            command_spawner: Box::new(TestCommandSpawner {
+                should_fail: true,
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+
+        app.change_directory(temp_dir.path()).unwrap();
+
+        assert_eq!(
+            app.status_message,
+            Some("Failed to run the on cd command: spawn failed".into())
+        );
+    }
+
+    #[test]
+    fn count_prefix_followed_by_enter_selects_row() {
+        let temp_dir = tempfile::tempdir().unwrap();
+
+        for i in 0..15 {
+            File::create(temp_dir.path().join(format!("file_{i:02}.txt"))).unwrap();
+        }
+
+        let mut app = App::default();
+        app.change_directory(temp_dir.path()).unwrap();
+
+        let _ = app.handle_key_event(KeyCode::Char('1').into(), KeyModifiers::NONE);
+        let _ = app.handle_key_event(KeyCode::Char('2').into(), KeyModifiers::NONE);
+        let _ = app.handle_key_event(KeyCode::Enter.into(), KeyModifiers::NONE);
+
+        assert_eq!(app.list_state.selected(), Some(11));
+        assert_eq!(app.current_directory, temp_dir.path());
+    }
+
+    #[test]
+    fn count_prefix_out_of_range_selects_last_row() {
+        let mut app = create_test_app();
+
+        let _ = app.handle_key_event(KeyCode::Char('1').into(), KeyModifiers::NONE);
+        let _ = app.handle_key_event(KeyCode::Char('2').into(), KeyModifiers::NONE);
+        let _ = app.handle_key_event(KeyCode::Enter.into(), KeyModifiers::NONE);
+
+        assert_eq!(app.list_state.selected(), Some(3));
+    }
+
+    #[test]
+    fn count_prefix_is_discarded_by_other_keys() {
+        let mut app = create_test_app();
+
+        let _ = app.handle_key_event(KeyCode::Char('2').into(), KeyModifiers::NONE);
+        let _ = app.handle_key_event(KeyCode::Char('j').into(), KeyModifiers::NONE);
+
+        assert_eq!(app.count_prefix, None);
+        assert_eq!(app.list_state.selected(), Some(0));
+    }
+
+    fn create_auto_enter_test_app(auto_enter_single: AutoEnterSingle) -> (tempfile::TempDir, App) {
+        let temp_dir = tempfile::tempdir().unwrap();
+
+        std::fs::create_dir(temp_dir.path().join("apple")).unwrap();
+        std::fs::create_dir(temp_dir.path().join("banana")).unwrap();
+        File::create(temp_dir.path().join("cherry.txt")).unwrap();
+
+        let mut app = App::new(Config {
+            auto_enter_single: Some(auto_enter_single),
+            ..Default::default()
+        });
+        app.change_directory(temp_dir.path()).unwrap();
+
+        (temp_dir, app)
+    }
+
+    #[test]
+    fn renders_session_stats_after_a_few_actions() {
+        let (_temp_dir, mut app) = create_auto_enter_test_app(AutoEnterSingle::Enter);
+        let mut terminal = Terminal::new(TestBackend::new(80, 9)).unwrap();
+
+        // Quick-jump into the first directory (`a` is skipped since it's the first letter of
+        // `apple`) and go back up
+        terminal
+            .draw(|frame| frame.render_widget(&mut app, frame.area()))
+            .unwrap();
+        app.handle_key_event(KeyCode::Char('s').into(), KeyModifiers::NONE)
+            .unwrap();
+        app.handle_key_event(KeyCode::Char('h').into(), KeyModifiers::NONE)
+            .unwrap();
+
+        // Start a search twice
+        for _ in 0..2 {
+            app.handle_key_event(KeyCode::Char('/').into(), KeyModifiers::NONE)
+                .unwrap();
+            app.handle_key_event(KeyCode::Esc.into(), KeyModifiers::NONE)
+                .unwrap();
+        }
+
+        app.handle_key_event(KeyCode::Char('S').into(), KeyModifiers::SHIFT)
+            .unwrap();
+        assert!(app.show_session_stats);
+        assert_eq!(app.session_stats.directories_visited, 3);
+        assert_eq!(app.session_stats.searches, 2);
+        assert_eq!(app.session_stats.quick_jumps, 1);
+
+        // Only the popup is rendered, since the header shows the (random) temporary directory
+        let now = app.session_stats.started_at + Duration::from_secs(125);
+        let mut buffer = Buffer::empty(Rect::new(0, 0, 60, 8));
+        app.render_session_stats_popup(now, &mut buffer);
+
+        assert_snapshot!(format!("{buffer:?}"));
+    }
+
+    #[test]
+    fn every_enter_key_changes_directory_to_selected_entry() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir(temp_dir.path().join("apple")).unwrap();
+
+        for (code, modifiers) in [
+            (KeyCode::Enter, KeyModifiers::NONE),
+            (KeyCode::Char('l'), KeyModifiers::NONE),
+            (KeyCode::Right, KeyModifiers::NONE),
+            (KeyCode::Char(';'), KeyModifiers::NONE),
+        ] {
+            let mut app = App::new(Config {
+                enter_keys: vec![';'],
+                ..Default::default()
+            });
+            app.change_directory(temp_dir.path()).unwrap();
+            app.list_state.select(Some(0));
+
+            app.handle_key_event(code.into(), modifiers).unwrap();
+
+            assert_eq!(
+                app.current_directory,
+                temp_dir.path().join("apple"),
+                "{code:?} didn't enter the directory"
+            );
+        }
+    }
+
+    #[test]
+    fn toggle_repository_root_jumps_to_root_and_back() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let repository = temp_dir.path().join("repo");
+        let nested = repository.join("src").join("app");
+        std::fs::create_dir_all(repository.join(".git")).unwrap();
+        std::fs::create_dir_all(&nested).unwrap();
+
+        let mut app = App::new(Config::default());
+        app.change_directory(&nested).unwrap();
+
+        app.handle_key_event(KeyCode::Char('R').into(), KeyModifiers::SHIFT)
+            .unwrap();
+        assert_eq!(app.current_directory, repository);
+
+        app.handle_key_event(KeyCode::Char('R').into(), KeyModifiers::SHIFT)
+            .unwrap();
+        assert_eq!(app.current_directory, nested);
+
+        // Outside of a repository nothing changes
+        app.change_directory(temp_dir.path()).unwrap();
+        app.handle_key_event(KeyCode::Char('R').into(), KeyModifiers::SHIFT)
+            .unwrap();
+        assert_eq!(app.current_directory, temp_dir.path());
+        assert_eq!(
+            app.status_message,
+            Some("Not inside a git repository".into())
+        );
+    }
+
+    #[test]
+    fn sibling_navigation_moves_between_directories_of_the_parent() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        for name in ["alpha", "beta", "gamma"] {
+            std::fs::create_dir(temp_dir.path().join(name)).unwrap();
+        }
+        File::create(temp_dir.path().join("notes.txt")).unwrap();
+
+        let mut app = App::new(Config::default());
+        app.change_directory(temp_dir.path().join("alpha")).unwrap();
+
+        let mut press = |c: char| {
+            app.handle_key_event(KeyCode::Char(c).into(), KeyModifiers::NONE)
+                .unwrap();
+            app.current_directory.clone()
+        };
+
+        assert_eq!(press('}'), temp_dir.path().join("beta"));
+        assert_eq!(press('}'), temp_dir.path().join("gamma"));
+
+        // We stay at the ends unless wrapping is enabled
+        assert_eq!(press('}'), temp_dir.path().join("gamma"));
+        assert_eq!(press('{'), temp_dir.path().join("beta"));
+        assert_eq!(press('{'), temp_dir.path().join("alpha"));
+        assert_eq!(press('{'), temp_dir.path().join("alpha"));
+
+        app.config.wrap_siblings = true;
+        app.handle_key_event(KeyCode::Char('{').into(), KeyModifiers::NONE)
+            .unwrap();
+        assert_eq!(app.current_directory, temp_dir.path().join("gamma"));
+    }
+
+    fn type_search_query(app: &mut App, query: &str) {
+        let _ = app.handle_key_event(KeyCode::Char('/').into(), KeyModifiers::NONE);
+
+        for c in query.chars() {
+            let _ = app.handle_key_event(KeyCode::Char(c).into(), KeyModifiers::NONE);
+        }
+    }
+
+    #[test]
+    fn tab_in_search_mode_enters_first_directory_and_keeps_searching() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let project = temp_dir.path().join("project");
+
+        std::fs::create_dir(&project).unwrap();
+        std::fs::create_dir(project.join("src")).unwrap();
+        File::create(project.join("notes.txt")).unwrap();
+        File::create(temp_dir.path().join("proposal.txt")).unwrap();
+
+        let mut app = App::default();
+        app.change_directory(temp_dir.path()).unwrap();
+
+        type_search_query(&mut app, "pro");
+        assert_eq!(get_entry_names(&app), vec!["project", "proposal.txt"]);
+
+        app.handle_key_event(KeyCode::Tab.into(), KeyModifiers::NONE)
+            .unwrap();
+
+        assert_eq!(app.current_directory, project);
+        assert_eq!(app.input_mode, InputMode::Search);
+        assert_eq!(app.search_input.value, "");
+
+        let _ = app.handle_key_event(KeyCode::Char('s').into(), KeyModifiers::NONE);
+        assert_eq!(get_entry_names(&app), vec!["src", "notes.txt"]);
+    }
+
+    #[test]
+    fn tab_in_search_mode_keeps_the_query_when_the_directory_is_not_entered() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let large_dir = temp_dir.path().join("large_dir");
+        std::fs::create_dir(&large_dir).unwrap();
+        File::create(temp_dir.path().join("large_notes.txt")).unwrap();
+        File::create(temp_dir.path().join("other.txt")).unwrap();
+
+        for i in 0..5 {
+            File::create(large_dir.join(format!("file_{i}.txt"))).unwrap();
+        }
+
+        let mut app = App::new(Config {
+            large_directory_threshold: Some(3),
+            ..Default::default()
+        });
+        app.change_directory(temp_dir.path()).unwrap();
+
+        type_search_query(&mut app, "large");
+        app.handle_key_event(KeyCode::Tab.into(), KeyModifiers::NONE)
+            .unwrap();
+
+        // The confirmation holds us back, so the query still matches what's listed
+        assert!(app.pending_confirmation.is_some());
+        assert_eq!(app.current_directory, temp_dir.path());
+        assert_eq!(app.search_input.value, "large");
+        assert_eq!(get_entry_names(&app), vec!["large_dir", "large_notes.txt"]);
+    }
+
+    #[test]
+    fn auto_enter_single_enters_only_matching_directory_after_pause() {
+        let (temp_dir, mut app) = create_auto_enter_test_app(AutoEnterSingle::Enter);
+
+        type_search_query(&mut app, "ban");
+        let typed_at = app.single_match_time.unwrap();
+
+        // The user might still be typing, so nothing should happen yet
+        app.auto_enter_single_match(typed_at).unwrap();
+        assert_eq!(app.current_directory, temp_dir.path());
+
+        app.auto_enter_single_match(typed_at + App::AUTO_ENTER_DELAY)
+            .unwrap();
+
+        assert_eq!(app.current_directory, temp_dir.path().join("banana"));
+        assert_eq!(app.input_mode, InputMode::Normal);
+        assert!(!app.should_exit);
+    }
 
-        App::render_header(header_area, buf);
+    #[test]
+    fn auto_enter_single_exits_with_only_matching_directory() {
+        let (temp_dir, mut app) = create_auto_enter_test_app(AutoEnterSingle::Exit);
 
-        self.render_footer(footer_area, buf);
-        self.render_selected_tab_title(selected_tab_title_area, buf);
-        self.render_list(list_area, buf);
+        type_search_query(&mut app, "ban");
 
-        if self.show_help {
-            self.render_help_popup(buf);
-        }
+        let typed_at = app.single_match_time.unwrap();
+        app.auto_enter_single_match(typed_at + App::AUTO_ENTER_DELAY)
+            .unwrap();
+
+        assert!(app.should_exit);
+        assert_eq!(
+            app.exit_outcome,
+            Some(RunOutcome::ChangedTo(temp_dir.path().join("banana")))
+        );
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use crate::entry::Entry;
+    #[test]
+    fn auto_enter_single_ignores_multiple_or_file_matches() {
+        let (_temp_dir, mut app) = create_auto_enter_test_app(AutoEnterSingle::Enter);
 
-    use super::*;
+        // Matches both `apple` and `banana`
+        type_search_query(&mut app, "a");
+        assert_eq!(app.single_match_time, None);
 
-    use insta::assert_snapshot;
-    use ratatui::{backend::TestBackend, Terminal};
+        // Matches only a file
+        let _ = app.handle_key_event(KeyCode::Esc.into(), KeyModifiers::NONE);
+        type_search_query(&mut app, "cher");
+        assert_eq!(app.single_match_time, None);
+    }
 
-    fn create_test_app() -> App {
-        App {
-            current_directory: PathBuf::from("/home/user"),
-            list_mode: ListMode::Directory,
-            entry_list: EntryList {
-                items: vec![
-                    Entry {
-                        path: PathBuf::from("/home/user/.git/"),
-                        kind: EntryKind::Directory,
-                        name: ".git".into(),
-                    },
-                    Entry {
-                        path: PathBuf::from("/home/user/dir1/"),
-                        kind: EntryKind::Directory,
-                        name: "dir1".into(),
-                    },
-                    Entry {
-                        path: PathBuf::from("/home/user/.gitignore"),
-                        kind: EntryKind::File { extension: None },
-                        name: ".gitignore".into(),
-                    },
-                    Entry {
-                        path: PathBuf::from("/home/user/Cargo.toml"),
-                        kind: EntryKind::File {
-                            extension: Some("toml".into()),
-                        },
-                        name: "Cargo.toml".into(),
-                    },
-                ],
+    #[test]
+    fn renders_capped_search_results_indicator() {
+        let mut app = App {
+            config: Config {
+                max_search_results: Some(1),
                 ..Default::default()
             },
-            ..Default::default()
-        }
-    }
+            ..create_test_app()
+        };
 
-    #[test]
-    fn renders_correctly() {
-        let mut app = create_test_app();
-        let mut terminal = Terminal::new(TestBackend::new(80, 9)).unwrap();
+        // Both `.git` and `.gitignore` match, but only the first one is kept
+        type_search_query(&mut app, "git");
 
+        let mut terminal = Terminal::new(TestBackend::new(80, 9)).unwrap();
         terminal
             .draw(|frame| frame.render_widget(&mut app, frame.area()))
             .unwrap();
 
+        assert_eq!(app.entry_list.get_filtered_entries().len(), 1);
         assert_snapshot!(terminal.backend());
     }
 
+    fn get_entry_names(app: &App) -> Vec<&str> {
+        app.entry_list
+            .get_filtered_entries()
+            .iter()
+            .map(|x| x.name.as_str())
+            .collect()
+    }
+
     #[test]
-    fn renders_correctly_with_help_popup() {
-        let mut app = create_test_app();
-        app.show_help = true;
+    fn cycle_directory_grouping_lists_directories_first_last_and_mixed() {
+        let temp_dir = tempfile::tempdir().unwrap();
+
+        std::fs::create_dir(temp_dir.path().join("beta")).unwrap();
+        std::fs::create_dir(temp_dir.path().join("delta")).unwrap();
+        File::create(temp_dir.path().join("alpha.txt")).unwrap();
+        File::create(temp_dir.path().join("gamma.txt")).unwrap();
+
+        let mut app = App::default();
+        app.change_directory(temp_dir.path()).unwrap();
+
+        assert_eq!(app.config.directory_grouping, DirectoryGrouping::First);
+        assert_eq!(
+            get_entry_names(&app),
+            vec!["beta", "delta", "alpha.txt", "gamma.txt"]
+        );
+
+        let _ = app.handle_key_event(KeyCode::Char('F').into(), KeyModifiers::SHIFT);
+
+        assert_eq!(app.config.directory_grouping, DirectoryGrouping::Last);
+        assert_eq!(
+            get_entry_names(&app),
+            vec!["alpha.txt", "gamma.txt", "beta", "delta"]
+        );
+
+        let _ = app.handle_key_event(KeyCode::Char('F').into(), KeyModifiers::SHIFT);
+
+        assert_eq!(app.config.directory_grouping, DirectoryGrouping::Mixed);
+        assert_eq!(
+            get_entry_names(&app),
+            vec!["alpha.txt", "beta", "delta", "gamma.txt"]
+        );
+
+        let _ = app.handle_key_event(KeyCode::Char('F').into(), KeyModifiers::SHIFT);
+
+        assert_eq!(app.config.directory_grouping, DirectoryGrouping::First);
+        assert_eq!(
+            get_entry_names(&app),
+            vec!["beta", "delta", "alpha.txt", "gamma.txt"]
+        );
+    }
 
-        let mut terminal = Terminal::new(TestBackend::new(80, 24)).unwrap();
+    #[test]
+    fn toggle_hidden_files_keeps_the_selected_entry() {
+        let temp_dir = tempfile::tempdir().unwrap();
+
+        std::fs::create_dir(temp_dir.path().join(".config")).unwrap();
+        std::fs::create_dir(temp_dir.path().join("src")).unwrap();
+        File::create(temp_dir.path().join(".env")).unwrap();
+        File::create(temp_dir.path().join("Cargo.toml")).unwrap();
+
+        let mut app = App::default();
+        app.change_directory(temp_dir.path()).unwrap();
+        assert_eq!(
+            get_entry_names(&app),
+            vec![".config", "src", ".env", "Cargo.toml"]
+        );
+
+        app.list_state.select(Some(3));
+        let _ = app.handle_key_event(KeyCode::Char('.').into(), KeyModifiers::NONE);
+
+        assert_eq!(get_entry_names(&app), vec!["src", "Cargo.toml"]);
+        assert_eq!(app.get_selected_entry().unwrap().name, "Cargo.toml");
+
+        let _ = app.handle_key_event(KeyCode::Char('.').into(), KeyModifiers::NONE);
+        assert_eq!(app.get_selected_entry().unwrap().name, "Cargo.toml");
+
+        // The selected entry is hidden, so the selection stays on the same row
+        app.list_state.select(Some(2));
+        let _ = app.handle_key_event(KeyCode::Char('.').into(), KeyModifiers::NONE);
+        assert_eq!(app.get_selected_entry().unwrap().name, "Cargo.toml");
+
+        app.list_state.select(Some(0));
+        let _ = app.handle_key_event(KeyCode::Char('.').into(), KeyModifiers::NONE);
+        assert_eq!(app.get_selected_entry().unwrap().name, "src");
+        assert_eq!(app.list_state.selected(), Some(1));
+    }
 
-        terminal
-            .draw(|frame| frame.render_widget(&mut app, frame.area()))
-            .unwrap();
+    #[test]
+    fn toggle_sort_direction_reverses_names_within_directories_and_files() {
+        let temp_dir = tempfile::tempdir().unwrap();
 
-        assert_snapshot!(terminal.backend());
+        std::fs::create_dir(temp_dir.path().join("alpha")).unwrap();
+        std::fs::create_dir(temp_dir.path().join("beta")).unwrap();
+        File::create(temp_dir.path().join("a.txt")).unwrap();
+        File::create(temp_dir.path().join("b.txt")).unwrap();
+
+        let mut app = App::default();
+        app.change_directory(temp_dir.path()).unwrap();
+
+        assert_eq!(
+            get_entry_names(&app),
+            vec!["alpha", "beta", "a.txt", "b.txt"]
+        );
+
+        let _ = app.handle_key_event(KeyCode::Char('O').into(), KeyModifiers::SHIFT);
+
+        assert!(app.config.sort_descending);
+        assert_eq!(
+            get_entry_names(&app),
+            vec!["beta", "alpha", "b.txt", "a.txt"]
+        );
+
+        let _ = app.handle_key_event(KeyCode::Char('O').into(), KeyModifiers::SHIFT);
+
+        assert!(!app.config.sort_descending);
+        assert_eq!(
+            get_entry_names(&app),
+            vec!["alpha", "beta", "a.txt", "b.txt"]
+        );
     }
 
     #[test]
-    fn renders_correctly_with_help_popup_after_key_event() {
-        let mut app = create_test_app();
-        app.handle_key_event(KeyCode::Char('?').into(), KeyModifiers::NONE)
-            .unwrap();
+    fn renders_search_hit_with_theme_style() {
+        let mut app = App {
+            config: Config {
+                theme: Theme {
+                    search_hit: Style::default().bold().fg(Color::Yellow),
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+            ..create_test_app()
+        };
 
-        let mut terminal = Terminal::new(TestBackend::new(80, 24)).unwrap();
+        type_search_query(&mut app, "git");
 
+        let mut terminal = Terminal::new(TestBackend::new(80, 9)).unwrap();
         terminal
             .draw(|frame| frame.render_widget(&mut app, frame.area()))
             .unwrap();
 
+        // The text snapshot doesn't include styles, so we check the search hit cells of the second
+        // (unselected) row, `.gitignore`, directly
+        let buffer = terminal.backend().buffer();
+        for x in 3..6 {
+            assert_eq!(buffer[(x, 4)].fg, Color::Yellow);
+            assert!(buffer[(x, 4)].modifier.contains(Modifier::BOLD));
+        }
+        assert_eq!(buffer[(6, 4)].fg, Color::DarkGray);
+
         assert_snapshot!(terminal.backend());
     }
 
+    #[cfg(feature = "archives")]
     #[test]
-    fn renders_correctly_without_help_popup_after_key_event_toggle() {
-        let mut app = create_test_app();
-        app.show_help = true;
-        app.handle_key_event(KeyCode::Char('?').into(), KeyModifiers::NONE)
-            .unwrap();
+    fn entering_zip_archive_lists_its_top_level_members() {
+        use std::io::Write;
 
-        let mut terminal = Terminal::new(TestBackend::new(80, 24)).unwrap();
+        let temp_dir = tempfile::tempdir().unwrap();
+        let archive_path = temp_dir.path().join("archive.zip");
 
-        terminal
-            .draw(|frame| frame.render_widget(&mut app, frame.area()))
-            .unwrap();
+        let mut writer = zip::ZipWriter::new(File::create(&archive_path).unwrap());
+        let options = zip::write::SimpleFileOptions::default();
+        writer.start_file("notes.txt", options).unwrap();
+        writer.write_all(b"notes").unwrap();
+        writer.start_file("src/main.rs", options).unwrap();
+        writer.finish().unwrap();
 
-        assert_snapshot!(terminal.backend());
+        let mut app = App::default();
+        app.change_directory(temp_dir.path()).unwrap();
+
+        let _ = app.handle_key_event(KeyCode::Enter.into(), KeyModifiers::NONE);
+
+        assert!(app.is_browsing_archive);
+        assert_eq!(app.current_directory, archive_path);
+        assert_eq!(get_entry_names(&app), vec!["src", "notes.txt"]);
+
+        // The members are read-only
+        let _ = app.handle_key_event(KeyCode::Enter.into(), KeyModifiers::NONE);
+        assert_eq!(app.current_directory, archive_path);
+        assert!(!app.should_exit);
+
+        // Exiting from inside the archive returns the directory that contains it
+        assert_eq!(app.get_exit_directory(), temp_dir.path());
+
+        let _ = app.handle_key_event(KeyCode::Char('h').into(), KeyModifiers::NONE);
+        assert!(!app.is_browsing_archive);
+        assert_eq!(app.current_directory, temp_dir.path());
     }
 
     #[test]
-    fn renders_correctly_with_search_input_after_key_events() {
-        let mut app = create_test_app();
-        app.handle_key_event(KeyCode::Char('/').into(), KeyModifiers::NONE)
-            .unwrap();
-        app.handle_key_event(KeyCode::Char('t').into(), KeyModifiers::NONE)
-            .unwrap();
-        app.handle_key_event(KeyCode::Char('e').into(), KeyModifiers::NONE)
-            .unwrap();
-        app.handle_key_event(KeyCode::Char('s').into(), KeyModifiers::NONE)
-            .unwrap();
-        app.handle_key_event(KeyCode::Char('t').into(), KeyModifiers::NONE)
+    fn get_start_directory_falls_back_when_current_directory_is_unavailable() {
+        assert_eq!(
+            get_start_directory(Result::Ok(PathBuf::from("/work")), None),
+            (PathBuf::from("/work"), None)
+        );
+
+        let not_found = || io::Error::from(io::ErrorKind::NotFound);
+
+        let (path, status_message) =
+            get_start_directory(Err(not_found()), Some(PathBuf::from("/home/user")));
+        assert_eq!(path, PathBuf::from("/home/user"));
+        assert!(status_message
+            .unwrap()
+            .contains("showing '/home/user' instead"));
+
+        let (path, status_message) = get_start_directory(Err(not_found()), None);
+        assert_eq!(path, PathBuf::from(MAIN_SEPARATOR_STR));
+        assert!(status_message.is_some());
+    }
+
+    #[test]
+    fn try_new_applies_default_query_until_it_is_reset() {
+        let config = Config {
+            default_query: Some("cargo".into()),
+            ..Default::default()
+        };
+
+        // The tests run in the root of the crate
+        let mut app = App::try_new(ListMode::Directory, config).unwrap();
+
+        assert_eq!(app.search_input.value, "cargo");
+        assert_eq!(app.input_mode, InputMode::Normal);
+        assert!(get_entry_names(&app).contains(&"Cargo.toml"));
+        assert!(!get_entry_names(&app).contains(&"src"));
+
+        app.handle_key_event(KeyCode::Char('_').into(), KeyModifiers::NONE)
             .unwrap();
 
-        let mut terminal = Terminal::new(TestBackend::new(80, 9)).unwrap();
+        assert!(app.search_input.is_empty());
+        assert!(get_entry_names(&app).contains(&"src"));
+    }
+
+    #[test]
+    fn renders_paths_read_from_a_reader() {
+        let input = "/home/user/projects/website\n\n/home/user/missing file.txt\r\n";
+        let paths = read_paths(io::Cursor::new(input)).unwrap();
+
+        let mut app = App::try_from_paths(paths, Config::default()).unwrap();
+        let mut terminal = Terminal::new(TestBackend::new(80, 7)).unwrap();
 
         terminal
             .draw(|frame| frame.render_widget(&mut app, frame.area()))
             .unwrap();
 
         assert_snapshot!(terminal.backend());
+
+        // The paths don't have to exist to be picked
+        app.list_state.select(Some(1));
+        app.handle_key_event(KeyCode::Enter.into(), KeyModifiers::NONE)
+            .unwrap();
+
+        assert_eq!(
+            app.run(&mut terminal).unwrap(),
+            RunOutcome::SelectedFile(PathBuf::from("/home/user/missing file.txt"))
+        );
     }
 
     #[test]
-    fn renders_correctly_with_search_input() {
-        let mut app = create_test_app();
-        app.input_mode = InputMode::Search;
-        app.search_input.value = "test".into();
-        app.search_input.index = 4;
+    fn try_new_starts_in_configured_list_mode() {
+        let config = Config {
+            start_list_mode: ListMode::Frecent,
+            ..Default::default()
+        };
 
-        let mut terminal = Terminal::new(TestBackend::new(80, 9)).unwrap();
+        let app = App::try_new(config.start_list_mode, config).unwrap();
+
+        assert_eq!(app.list_mode, ListMode::Frecent);
+        assert_eq!(app.current_directory, env::current_dir().unwrap());
+    }
+
+    #[test]
+    fn renders_hint_for_empty_frecent_list() {
+        let mut app = App {
+            list_mode: ListMode::Frecent,
+            ..App::from_entries(Vec::new(), PathBuf::from("/home/user"))
+        };
 
+        let mut terminal = Terminal::new(TestBackend::new(80, 9)).unwrap();
         terminal
             .draw(|frame| frame.render_widget(&mut app, frame.area()))
             .unwrap();
@@ -887,148 +5354,207 @@ mod tests {
     }
 
     #[test]
-    fn first_item_is_preselected_after_render() {
-        let mut app = create_test_app();
-        let mut buffer = Buffer::empty(Rect::new(0, 0, 79, 10));
+    fn renders_files_with_extension_colors() {
+        let mut app = App {
+            config: Config {
+                theme: Theme {
+                    extension_colors: HashMap::from([
+                        ("toml".into(), Color::Green),
+                        ("gitignore".into(), Color::Blue),
+                    ]),
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+            ..create_test_app()
+        };
 
-        assert_eq!(app.list_state.selected(), None);
+        let mut terminal = Terminal::new(TestBackend::new(80, 9)).unwrap();
+        terminal
+            .draw(|frame| frame.render_widget(&mut app, frame.area()))
+            .unwrap();
 
-        app.render(buffer.area, &mut buffer);
+        // The text snapshot doesn't include styles, so we check the colors of the rows directly,
+        // `.gitignore` has no extension (it's a hidden file) so it keeps the default file style
+        let buffer = terminal.backend().buffer();
+        assert_eq!(buffer[(2, 5)].fg, Color::DarkGray);
+        assert_eq!(buffer[(2, 6)].fg, Color::Green);
 
-        assert_eq!(app.list_state.selected(), Some(0));
+        assert_snapshot!(terminal.backend());
     }
 
+    #[cfg(unix)]
     #[test]
-    fn handle_key_event() {
-        let mut app = create_test_app();
-
-        // Make sure we have 4 items
-        assert_eq!(app.entry_list.len(), 4);
-
-        let _ = app.handle_key_event(KeyCode::Char('q').into(), KeyModifiers::NONE);
-        assert!(app.should_exit);
-
-        let _ = app.handle_key_event(KeyCode::Esc.into(), KeyModifiers::NONE);
-        assert!(app.should_exit);
+    fn entering_directory_with_name_that_is_not_valid_utf8_uses_real_path() {
+        use std::{ffi::OsStr, os::unix::ffi::OsStrExt};
 
-        let _ = app.handle_key_event(KeyCode::Char('j').into(), KeyModifiers::NONE);
-        assert_eq!(app.list_state.selected(), Some(0));
+        let temp_dir = tempfile::tempdir().unwrap();
+        let sub_dir = temp_dir.path().join(OsStr::from_bytes(b"caf\xe9"));
 
-        let _ = app.handle_key_event(KeyCode::Down.into(), KeyModifiers::NONE);
-        assert_eq!(app.list_state.selected(), Some(1));
+        // Some file systems (e.g. APFS) reject such names, there's nothing to test there
+        if std::fs::create_dir(&sub_dir).is_err() {
+            return;
+        }
 
-        // press down so that we can go back up more than once
-        let _ = app.handle_key_event(KeyCode::Down.into(), KeyModifiers::NONE);
+        let mut app = App::default();
+        app.change_directory(temp_dir.path()).unwrap();
 
-        let _ = app.handle_key_event(KeyCode::Char('k').into(), KeyModifiers::NONE);
-        assert_eq!(app.list_state.selected(), Some(1));
+        // The name is only used for display, it's lossy
+        assert_eq!(get_entry_names(&app), vec!["caf\u{fffd}"]);
 
-        let _ = app.handle_key_event(KeyCode::Up.into(), KeyModifiers::NONE);
-        assert_eq!(app.list_state.selected(), Some(0));
+        let _ = app.handle_key_event(KeyCode::Char('L').into(), KeyModifiers::SHIFT);
+        assert_eq!(
+            app.exit_outcome,
+            Some(RunOutcome::ChangedTo(sub_dir.clone()))
+        );
 
-        let _ = app.handle_key_event(KeyCode::Char('G').into(), KeyModifiers::SHIFT);
-        assert_eq!(app.list_state.selected(), Some(usize::MAX));
+        let _ = app.handle_key_event(KeyCode::Enter.into(), KeyModifiers::NONE);
+        assert_eq!(app.current_directory, sub_dir);
+    }
 
-        let _ = app.handle_key_event(KeyCode::Char('g').into(), KeyModifiers::NONE);
-        let _ = app.handle_key_event(KeyCode::Char('g').into(), KeyModifiers::NONE);
-        assert_eq!(app.list_state.selected(), Some(0));
+    #[test]
+    fn bookmarked_directory_is_listed_in_bookmark_mode_and_entered() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let projects = temp_dir.path().join("projects");
+        std::fs::create_dir(&projects).unwrap();
+        File::create(temp_dir.path().join("notes.txt")).unwrap();
+
+        let mut app = App::default();
+        app.change_directory(temp_dir.path()).unwrap();
+
+        let _ = app.handle_key_event(KeyCode::Char('B').into(), KeyModifiers::SHIFT);
+        assert_eq!(app.status_message, Some("Bookmarked 'projects'".into()));
+
+        // Files can't be bookmarked
+        app.list_state.select(Some(1));
+        let _ = app.handle_key_event(KeyCode::Char('B').into(), KeyModifiers::SHIFT);
+        assert_eq!(
+            app.status_message,
+            Some("Only directories can be bookmarked".into())
+        );
+
+        let _ = app.handle_key_event(KeyCode::Char('b').into(), KeyModifiers::CONTROL);
+        assert_eq!(app.list_mode, ListMode::Bookmark);
+        assert_eq!(get_entry_names(&app), vec![projects.to_str().unwrap()]);
+
+        let _ = app.handle_key_event(KeyCode::Enter.into(), KeyModifiers::NONE);
+        assert_eq!(app.list_mode, ListMode::Directory);
+        assert_eq!(app.current_directory, projects);
 
-        let _ = app.handle_key_event(KeyCode::End.into(), KeyModifiers::NONE);
-        assert_eq!(app.list_state.selected(), Some(usize::MAX));
+        // Removing the bookmark from the bookmarks list takes it off the list right away
+        let _ = app.handle_key_event(KeyCode::Char('b').into(), KeyModifiers::CONTROL);
+        let _ = app.handle_key_event(KeyCode::Char('B').into(), KeyModifiers::SHIFT);
+        assert!(get_entry_names(&app).is_empty());
+    }
 
-        let _ = app.handle_key_event(KeyCode::Home.into(), KeyModifiers::NONE);
-        assert_eq!(app.list_state.selected(), Some(0));
+    #[test]
+    fn renders_bookmark_list() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let mut app = App {
+            show_path_basenames: true,
+            ..create_test_app()
+        };
 
-        let _ = app.handle_key_event(KeyCode::Char('d').into(), KeyModifiers::CONTROL);
-        assert_eq!(app.list_mode, ListMode::Directory);
+        for name in ["projects", "Documents", "gone"] {
+            let path = temp_dir.path().join(name);
+            std::fs::create_dir(&path).unwrap();
+            app.bookmarks.toggle(path).unwrap();
+        }
 
-        let _ = app.handle_key_event(KeyCode::Char('f').into(), KeyModifiers::CONTROL);
-        assert_eq!(app.list_mode, ListMode::Frecent);
+        // A bookmark that doesn't exist anymore isn't listed
+        std::fs::remove_dir(temp_dir.path().join("gone")).unwrap();
 
-        let _ = app.handle_key_event(KeyCode::Char('d').into(), KeyModifiers::CONTROL);
-        assert_eq!(app.list_mode, ListMode::Directory);
+        app.change_list_mode(ListMode::Bookmark).unwrap();
 
-        let _ = app.handle_key_event(KeyCode::Char('?').into(), KeyModifiers::NONE);
-        assert!(app.show_help);
+        let mut terminal = Terminal::new(TestBackend::new(80, 9)).unwrap();
 
-        let _ = app.handle_key_event(KeyCode::Char('/').into(), KeyModifiers::NONE);
-        assert_eq!(app.input_mode, InputMode::Search);
+        terminal
+            .draw(|frame| frame.render_widget(&mut app, frame.area()))
+            .unwrap();
 
-        let _ = app.handle_key_event(KeyCode::Esc.into(), KeyModifiers::NONE);
-        assert_eq!(app.input_mode, InputMode::Normal);
+        assert_snapshot!(terminal.backend());
     }
 
     #[test]
-    fn search_input_backspace() {
-        let mut app = create_test_app();
-        app.input_mode = InputMode::Search;
-        app.search_input.value = "test".into();
-        app.search_input.index = 4;
-
-        let _ = app.handle_key_event(KeyCode::Backspace.into(), KeyModifiers::NONE);
-        assert_eq!(app.search_input.value, "tes".to_string());
-        assert_eq!(app.search_input.index, 3);
+    fn toggle_path_basenames_switches_frecent_display_form() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let project_dir = temp_dir.path().join("tiny-fe");
+        let documents_dir = temp_dir.path().join("Documents");
 
-        let _ = app.handle_key_event(KeyCode::Backspace.into(), KeyModifiers::NONE);
-        assert_eq!(app.search_input.value, "te".to_string());
-        assert_eq!(app.search_input.index, 2);
+        std::fs::create_dir(&project_dir).unwrap();
+        std::fs::create_dir(&documents_dir).unwrap();
 
-        let _ = app.handle_key_event(KeyCode::Backspace.into(), KeyModifiers::NONE);
-        assert_eq!(app.search_input.value, "t".to_string());
-        assert_eq!(app.search_input.index, 1);
+        let mut app = App {
+            list_mode: ListMode::Frecent,
+            entry_list: EntryList::from(vec![project_dir.clone(), documents_dir.clone()]),
+            ..Default::default()
+        };
 
-        let _ = app.handle_key_event(KeyCode::Backspace.into(), KeyModifiers::NONE);
-        assert_eq!(app.search_input.value, "".to_string());
-        assert_eq!(app.search_input.index, 0);
+        let full_paths = vec![
+            project_dir.to_str().unwrap(),
+            documents_dir.to_str().unwrap(),
+        ];
+        assert_eq!(get_entry_names(&app), full_paths);
 
-        let _ = app.handle_key_event(KeyCode::Backspace.into(), KeyModifiers::NONE);
-        assert_eq!(app.search_input.value, "".to_string());
-        assert_eq!(app.search_input.index, 0);
-    }
+        let _ = app.handle_key_event(KeyCode::Char('P').into(), KeyModifiers::SHIFT);
+        assert_eq!(get_entry_names(&app), vec!["tiny-fe", "Documents"]);
 
-    #[test]
-    fn search_input_backspace_with_no_input() {
-        let mut app = create_test_app();
-        app.input_mode = InputMode::Search;
-        app.search_input.value = "".into();
-        app.search_input.index = 0;
+        // Actions still operate on the full path
+        let _ = app.handle_key_event(KeyCode::Char('L').into(), KeyModifiers::SHIFT);
+        assert_eq!(
+            app.exit_outcome,
+            Some(RunOutcome::ChangedTo(project_dir.clone()))
+        );
 
-        let _ = app.handle_key_event(KeyCode::Backspace.into(), KeyModifiers::NONE);
-        assert_eq!(app.input_mode, InputMode::Normal);
+        let _ = app.handle_key_event(KeyCode::Char('P').into(), KeyModifiers::SHIFT);
+        assert_eq!(get_entry_names(&app), full_paths);
     }
 
     #[test]
-    fn search_works_correctly() {
-        let mut app = create_test_app();
-        app.input_mode = InputMode::Search;
+    fn content_search_includes_files_containing_query() {
+        let temp_dir = tempfile::tempdir().unwrap();
 
-        let _ = app.handle_key_event(KeyCode::Char('g').into(), KeyModifiers::NONE);
-        let _ = app.handle_key_event(KeyCode::Char('i').into(), KeyModifiers::NONE);
-        let _ = app.handle_key_event(KeyCode::Char('t').into(), KeyModifiers::NONE);
+        std::fs::write(temp_dir.path().join("notes.txt"), "the quick brown fox\n").unwrap();
+        std::fs::write(temp_dir.path().join("other.txt"), "lorem ipsum\n").unwrap();
+        std::fs::write(temp_dir.path().join("fox.txt"), "").unwrap();
 
-        assert_eq!(app.search_input.value, "git".to_string());
-        assert_eq!(app.search_input.index, 3);
+        let mut app = App::default();
+        app.change_directory(temp_dir.path()).unwrap();
 
-        app.update_filtered_indices();
+        type_search_query(&mut app, "fox");
+        assert_eq!(get_entry_names(&app), vec!["fox.txt"]);
 
-        assert_eq!(app.entry_list.filtered_indices, Some(vec![0, 2]));
+        let _ = app.handle_key_event(KeyCode::Char('g').into(), KeyModifiers::CONTROL);
+        assert!(app.search_contents);
+        assert_eq!(get_entry_names(&app), vec!["fox.txt", "notes.txt"]);
+        assert_eq!(
+            app.entry_list
+                .get_content_snippet(app.entry_list.get_filtered_entries()[1]),
+            Some("the quick brown fox")
+        );
+
+        // The content search is only enabled for the current query
+        let _ = app.handle_key_event(KeyCode::Esc.into(), KeyModifiers::NONE);
+        type_search_query(&mut app, "fox");
+        assert!(!app.search_contents);
+        assert_eq!(get_entry_names(&app), vec!["fox.txt"]);
     }
 
     #[test]
-    fn search_renders_correctly() {
+    fn inverted_search_shows_entries_that_do_not_match() {
         let mut app = create_test_app();
-        app.input_mode = InputMode::Search;
-
-        let _ = app.handle_key_event(KeyCode::Char('g').into(), KeyModifiers::NONE);
-        let _ = app.handle_key_event(KeyCode::Char('i').into(), KeyModifiers::NONE);
-        let _ = app.handle_key_event(KeyCode::Char('t').into(), KeyModifiers::NONE);
 
-        let mut terminal = Terminal::new(TestBackend::new(80, 9)).unwrap();
+        type_search_query(&mut app, "git");
+        assert_eq!(get_entry_names(&app), vec![".git", ".gitignore"]);
 
-        terminal
-            .draw(|frame| frame.render_widget(&mut app, frame.area()))
-            .unwrap();
+        let _ = app.handle_key_event(KeyCode::Char('x').into(), KeyModifiers::CONTROL);
+        assert_eq!(get_entry_names(&app), vec!["dir1", "Cargo.toml"]);
 
+        let mut terminal = Terminal::new(TestBackend::new(60, 8)).unwrap();
+        terminal.draw(|frame| app.draw(frame)).unwrap();
         assert_snapshot!(terminal.backend());
+
+        let _ = app.handle_key_event(KeyCode::Char('x').into(), KeyModifiers::CONTROL);
+        assert_eq!(get_entry_names(&app), vec![".git", ".gitignore"]);
     }
 }
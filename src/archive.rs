@@ -0,0 +1,124 @@
+//! Read-only browsing of archives (currently only zip files), they are listed as if they were
+//! directories. Only the top-level members are listed and they can't be entered.
+
+use std::{collections::BTreeMap, fs::File, path::Path};
+
+use unicode_normalization::UnicodeNormalization;
+
+use crate::entry::{get_extension, Entry, EntryKind, EntryList};
+
+/// Returns true if the path looks like an archive that we know how to list
+pub fn is_supported_archive<T: AsRef<Path>>(path: T) -> bool {
+    path.as_ref()
+        .extension()
+        .is_some_and(|x| x.eq_ignore_ascii_case("zip"))
+}
+
+/// Lists the top-level members of the zip archive. The members get "virtual" paths that point
+/// inside the archive (e.g. `/home/user/archive.zip/src`), they don't exist on the file system.
+pub fn list_zip_entries<T: AsRef<Path>>(path: T) -> anyhow::Result<EntryList> {
+    let path = path.as_ref();
+    let archive = zip::ZipArchive::new(File::open(path)?)?;
+
+    // Members are stored with their full path, so nested members tell us about top-level
+    // directories that might not have an entry of their own
+    let mut members: BTreeMap<String, EntryKind> = BTreeMap::new();
+
+    for member_name in archive.file_names() {
+        let mut components = member_name.split('/').filter(|x| !x.is_empty());
+
+        let Some(name) = components.next() else {
+            continue;
+        };
+
+        let kind = if components.next().is_some() || member_name.ends_with('/') {
+            EntryKind::Directory
+        } else {
+            EntryKind::File {
+                extension: get_extension(name),
+            }
+        };
+
+        members.entry(name.to_string()).or_insert(kind);
+    }
+
+    let items = members
+        .into_iter()
+        .map(|(name, kind)| {
+            let entry_name: String = name.nfc().collect();
+
+            Entry {
+                path: path.join(&name),
+                kind,
+                name_lower: entry_name.to_lowercase(),
+                name: entry_name,
+                is_symlink: false,
+                is_executable: false,
+                permissions: None,
+                owner_ids: None,
+            }
+        })
+        .collect();
+
+    Ok(EntryList {
+        items,
+        ..Default::default()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write;
+
+    use zip::write::SimpleFileOptions;
+
+    use super::*;
+
+    #[test]
+    fn is_supported_archive_works_correctly() {
+        assert!(is_supported_archive("/home/user/archive.zip"));
+        assert!(is_supported_archive("/home/user/ARCHIVE.ZIP"));
+        assert!(!is_supported_archive("/home/user/archive.tar.gz"));
+        assert!(!is_supported_archive("/home/user/zip"));
+    }
+
+    #[test]
+    fn list_zip_entries_lists_top_level_members() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let archive_path = temp_dir.path().join("archive.zip");
+
+        let mut writer = zip::ZipWriter::new(File::create(&archive_path).unwrap());
+        let options = SimpleFileOptions::default();
+
+        writer.start_file("README.md", options).unwrap();
+        writer.write_all(b"# Readme").unwrap();
+        writer.add_directory("docs/", options).unwrap();
+        writer.start_file("src/main.rs", options).unwrap();
+        writer.write_all(b"fn main() {}").unwrap();
+        writer.start_file("src/lib.rs", options).unwrap();
+        writer.finish().unwrap();
+
+        let entry_list = list_zip_entries(&archive_path).unwrap();
+
+        let entries: Vec<(&str, &EntryKind)> = entry_list
+            .items
+            .iter()
+            .map(|x| (x.name.as_str(), &x.kind))
+            .collect();
+
+        assert_eq!(
+            entries,
+            vec![
+                (
+                    "README.md",
+                    &EntryKind::File {
+                        extension: Some("md".into())
+                    }
+                ),
+                ("docs", &EntryKind::Directory),
+                ("src", &EntryKind::Directory),
+            ]
+        );
+        assert_eq!(entry_list.items[2].path, archive_path.join("src"));
+    }
+}
@@ -0,0 +1,89 @@
+//! The bookmarked directories, listed in the bookmarks list mode (`Ctrl + b`).
+//!
+//! Pressing `B` bookmarks the selected directory (or removes the bookmark). The bookmarks are
+//! persisted in a plain text file with the full path of one bookmarked directory per line, in the
+//! order they were added.
+
+use std::path::{Path, PathBuf};
+
+use crate::path_lines::{read_path_lines, write_path_lines};
+
+#[derive(Debug, Default)]
+pub struct Bookmarks {
+    /// The file the bookmarks are persisted to, the bookmarks aren't persisted without it
+    file_path: Option<PathBuf>,
+
+    /// The bookmarked directories, the oldest bookmark first
+    paths: Vec<PathBuf>,
+}
+
+impl Bookmarks {
+    /// Loads the bookmarks from the given file, a missing file is treated as no bookmarks at all.
+    pub fn load_from_disk<T: AsRef<Path>>(file_path: T) -> anyhow::Result<Self> {
+        let file_path = file_path.as_ref().to_path_buf();
+        let paths = read_path_lines(&file_path)?;
+
+        Ok(Bookmarks {
+            file_path: Some(file_path),
+            paths,
+        })
+    }
+
+    pub fn save_to_disk(&self) -> anyhow::Result<()> {
+        let Some(file_path) = &self.file_path else {
+            return Ok(());
+        };
+
+        write_path_lines(file_path, &self.paths)
+    }
+
+    /// The bookmarked directories in the order they were added
+    pub fn get_paths(&self) -> &[PathBuf] {
+        &self.paths
+    }
+
+    pub fn contains(&self, path: &Path) -> bool {
+        self.paths.iter().any(|x| x == path)
+    }
+
+    /// Bookmarks the directory at the given path or removes the bookmark when it's already there,
+    /// and persists the bookmarks. Returns whether the directory is bookmarked now.
+    pub fn toggle<T: AsRef<Path>>(&mut self, path: T) -> anyhow::Result<bool> {
+        let path = path.as_ref();
+
+        let is_bookmarked = if self.contains(path) {
+            self.paths.retain(|x| x != path);
+            false
+        } else {
+            self.paths.push(path.to_path_buf());
+            true
+        };
+
+        self.save_to_disk()?;
+
+        Ok(is_bookmarked)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bookmarks_are_persisted_between_loads() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let file_path = temp_dir.path().join("bookmarks");
+
+        let mut bookmarks = Bookmarks::load_from_disk(&file_path).unwrap();
+        assert!(bookmarks.toggle("/home/user/zeta").unwrap());
+        assert!(bookmarks.toggle("/home/user/alpha").unwrap());
+
+        let mut bookmarks = Bookmarks::load_from_disk(&file_path).unwrap();
+        assert!(bookmarks.contains(Path::new("/home/user/zeta")));
+        assert!(!bookmarks.toggle("/home/user/zeta").unwrap());
+
+        let bookmarks = Bookmarks::load_from_disk(&file_path).unwrap();
+        assert_eq!(bookmarks.get_paths(), [PathBuf::from("/home/user/alpha")]);
+        assert!(!bookmarks.contains(Path::new("/home/user/zeta")));
+    }
+}
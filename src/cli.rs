@@ -0,0 +1,425 @@
+use std::{io::Write, path::PathBuf, time::Duration};
+
+use clap::{CommandFactory, Parser, Subcommand};
+use clap_complete::Shell;
+
+use ratatui::style::{Color, Style};
+
+use crate::{
+    app::ListMode,
+    config::{
+        get_default_data_file_path, parse_header_content, AutoEnterSingle, Config,
+        DirectoryGrouping, HeaderAlignment, HeaderContent, PermissionsFormat,
+    },
+    shell::{parse_variable_name, IntegrationShell},
+    theme::{parse_extension_color, parse_style, Theme},
+};
+
+/// A tiny TUI file explorer for quick navigation of directories.
+#[derive(Debug, Parser)]
+#[command(version, about)]
+pub struct Cli {
+    #[command(subcommand)]
+    pub command: Option<Command>,
+
+    /// The list mode to start in
+    #[arg(long, value_name = "MODE", default_value = "directory")]
+    pub mode: ListMode,
+
+    /// List the newline separated paths read from stdin instead (they don't have to exist), the
+    /// picked path is printed on exit
+    #[arg(long)]
+    pub stdin: bool,
+
+    /// Render below the prompt instead of on the alternate screen, the last frame stays in the
+    /// terminal's scrollback after exit
+    #[arg(long)]
+    pub no_alt_screen: bool,
+
+    /// The style of the matching part of the names while searching: `underline`, `bold`,
+    /// `reverse` or a color (e.g. `yellow` or `#ffaa00`) [default: underline]
+    #[arg(long, value_name = "STYLE", value_parser = parse_style)]
+    pub search_hit_style: Option<Style>,
+
+    /// The prompt shown in front of the search query (e.g. `> `) [default: /]
+    #[arg(long, value_name = "PROMPT")]
+    pub search_prompt: Option<String>,
+
+    /// Comma separated colors of files by their extension (e.g. `rs=green,md=blue`)
+    #[arg(long, value_name = "EXTENSION=COLOR", value_delimiter = ',', value_parser = parse_extension_color)]
+    pub extension_color: Vec<(String, Color)>,
+
+    /// Sort the names in descending order (can be toggled with `O` inside the app)
+    #[arg(long)]
+    pub sort_descending: bool,
+
+    /// Where the directories are listed: before the files, after them or mixed in with them (can
+    /// be cycled with `F` inside the app)
+    #[arg(long, value_name = "GROUPING", default_value = "first")]
+    pub directories: DirectoryGrouping,
+
+    /// Show the path that is about to be printed for the given number of milliseconds before
+    /// exiting
+    #[arg(long, value_name = "MILLISECONDS")]
+    pub confirm_exit: Option<u64>,
+
+    /// Hide entries starting with a `.` (can be toggled with `.` inside the app)
+    #[arg(long)]
+    pub hide_hidden: bool,
+
+    /// Comma separated names of hidden entries that should always be shown (e.g. `.git,.config`)
+    #[arg(long, value_name = "NAMES", value_delimiter = ',')]
+    pub always_show: Vec<String>,
+
+    /// Sort the entries passed to `--always-show` to the top of the listing
+    #[arg(long)]
+    pub always_show_on_top: bool,
+
+    /// Truncate entry names that are wider than the given number of columns
+    #[arg(long, value_name = "COLUMNS")]
+    pub max_name_width: Option<usize>,
+
+    /// Set the terminal title to the current directory while navigating
+    #[arg(long)]
+    pub set_title: bool,
+
+    /// Append `@` to symlinks and `*` to executables, like `ls -F`
+    #[arg(long)]
+    pub classify: bool,
+
+    /// Don't append `/` to the names of directories (they're still told apart by their style)
+    #[arg(long)]
+    pub no_trailing_slash: bool,
+
+    /// Don't show the quick-jump keys next to the directories until `H` is pressed (the keys work
+    /// either way)
+    #[arg(long)]
+    pub hide_hints: bool,
+
+    /// Stop searching after the given number of matches
+    #[arg(long, value_name = "COUNT")]
+    pub max_results: Option<usize>,
+
+    /// Act on the directory automatically when a search narrows the listing down to it
+    #[arg(long, value_name = "ACTION")]
+    pub auto_enter_single: Option<AutoEnterSingle>,
+
+    /// Ask for a confirmation before entering directories with more than the given number of
+    /// entries
+    #[arg(long, value_name = "ENTRIES")]
+    pub confirm_large_dirs: Option<usize>,
+
+    /// Cancel the confirmations that aren't answered within the given number of seconds
+    #[arg(long, value_name = "SECONDS")]
+    pub confirm_timeout: Option<u64>,
+
+    /// Exit (to the current directory) when no key is pressed for the given number of seconds
+    #[arg(long, value_name = "SECONDS")]
+    pub idle_timeout: Option<u64>,
+
+    /// Run the given command with the new directory as its last argument every time the current
+    /// directory changes (e.g. `logger -t tiny-fe`)
+    #[arg(long, value_name = "COMMAND")]
+    pub on_cd: Option<String>,
+
+    /// Append every visited directory (with a timestamp) to the given file
+    #[arg(long, value_name = "FILE")]
+    pub log: Option<PathBuf>,
+
+    /// Render the entries in columns (like `ls`) when their names are short enough, Tab and
+    /// Shift + Tab move between the columns
+    #[arg(long)]
+    pub grid: bool,
+
+    /// Keep the search query when changing directories and filter the new directory by it
+    #[arg(long)]
+    pub keep_search: bool,
+
+    /// A search query that filters the entries as soon as the app starts (e.g. a project prefix),
+    /// reset it with `_`
+    #[arg(long, value_name = "QUERY")]
+    pub default_query: Option<String>,
+
+    /// Comma separated prefixes of slow paths (e.g. `/mnt/nas,/net`), the metadata of the entries
+    /// in them is read in the background so that listing them doesn't hang
+    #[arg(long, value_name = "PREFIXES", value_delimiter = ',')]
+    pub slow_paths: Vec<PathBuf>,
+
+    /// Show the Unix permissions of the entries in front of their names
+    #[arg(long, value_name = "FORMAT")]
+    pub permissions: Option<PermissionsFormat>,
+
+    /// Show the owning user and group of the entries in front of their names (Unix only)
+    #[arg(long)]
+    pub owner: bool,
+
+    /// An extra key that opens the command palette (it can always be opened with `:`), pick one
+    /// that isn't used for jumping to entries
+    #[arg(long, value_name = "KEY")]
+    pub palette_key: Option<char>,
+
+    /// Comma separated extra keys that enter the selected entry (it can always be entered with
+    /// `Enter`, `l` or `→`), pick ones that aren't used for jumping to entries
+    #[arg(long, value_name = "KEYS", value_delimiter = ',')]
+    pub enter_keys: Vec<char>,
+
+    /// Wrap around at the ends when going to the next/previous directory in the parent (`}`/`{`)
+    #[arg(long)]
+    pub wrap_siblings: bool,
+
+    /// Show a line above the list with the parent directory and how to go up to it
+    #[arg(long)]
+    pub parent_context: bool,
+
+    /// What's shown in the header: `version`, `path` (the current directory), `mode` (the list
+    /// mode) or any other text [default: version]
+    #[arg(long, value_name = "CONTENT", value_parser = parse_header_content)]
+    pub header: Option<HeaderContent>,
+
+    /// Where the header is placed
+    #[arg(long, value_name = "ALIGNMENT", default_value = "center")]
+    pub header_align: HeaderAlignment,
+
+    /// Print `export NAME="path"` on exit instead of the bare path, so that a wrapper can `eval`
+    /// the output and capture the path in a variable
+    #[arg(long, value_name = "NAME", value_parser = parse_variable_name)]
+    pub export_var: Option<String>,
+
+    /// The file the bookmarked directories are stored in [default: ~/.tiny-fe-bookmarks]
+    #[arg(long, value_name = "PATH")]
+    pub bookmarks_file: Option<PathBuf>,
+
+    /// The file the numbered directory slots are stored in [default: ~/.tiny-fe-favorites]
+    #[arg(long, value_name = "PATH")]
+    pub favorites_file: Option<PathBuf>,
+
+    /// The file the pinned entries are stored in [default: ~/.tiny-fe-pins]
+    #[arg(long, value_name = "PATH")]
+    pub pins_file: Option<PathBuf>,
+
+    /// The file used for the directory stack [default: ~/.tiny-fe-stack]
+    #[arg(long, value_name = "PATH", global = true)]
+    pub stack_file: Option<PathBuf>,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum Command {
+    /// Print and remove the most recent directory from the directory stack (pushed with `M`)
+    Popd,
+
+    /// Print the shell functions that `cd` into the directory the app exits with, e.g.
+    /// `eval "$(tiny-fe init bash)"` or `tiny-fe init fish | source`
+    Init {
+        #[arg(value_enum)]
+        shell: IntegrationShell,
+    },
+
+    /// Print the completion script for the given shell
+    #[command(hide = true)]
+    Completions {
+        #[arg(value_enum)]
+        shell: Shell,
+    },
+}
+
+/// Writes the completion script of the CLI for the given shell.
+pub fn generate_completions<W: Write>(shell: Shell, writer: &mut W) {
+    clap_complete::generate(shell, &mut Cli::command(), "tiny-fe", writer);
+}
+
+impl Cli {
+    pub fn get_directory_stack_file(&self) -> Option<PathBuf> {
+        self.stack_file
+            .clone()
+            .or_else(|| get_default_data_file_path(".tiny-fe-stack"))
+    }
+}
+
+impl From<&Cli> for Config {
+    fn from(cli: &Cli) -> Self {
+        Config {
+            start_list_mode: cli.mode,
+            theme: Theme {
+                search_hit: cli
+                    .search_hit_style
+                    .unwrap_or_else(|| Theme::default().search_hit),
+                extension_colors: cli.extension_color.iter().cloned().collect(),
+                search_prompt: cli
+                    .search_prompt
+                    .clone()
+                    .unwrap_or_else(|| Theme::default().search_prompt),
+            },
+            sort_descending: cli.sort_descending,
+            directory_grouping: cli.directories,
+            exit_confirmation_duration: cli.confirm_exit.map(Duration::from_millis),
+            hide_hidden_files: cli.hide_hidden,
+            always_show: cli.always_show.clone(),
+            always_show_on_top: cli.always_show_on_top,
+            max_name_width: cli.max_name_width,
+            set_terminal_title: cli.set_title,
+            large_directory_threshold: cli.confirm_large_dirs,
+            confirmation_timeout: cli.confirm_timeout.map(Duration::from_secs),
+            idle_timeout: cli.idle_timeout.map(Duration::from_secs),
+            classify: cli.classify,
+            hide_directory_slash: cli.no_trailing_slash,
+            hide_quick_jump_hints: cli.hide_hints,
+            max_search_results: cli.max_results,
+            auto_enter_single: cli.auto_enter_single,
+            directory_stack_file: cli.get_directory_stack_file(),
+            pins_file: cli
+                .pins_file
+                .clone()
+                .or_else(|| get_default_data_file_path(".tiny-fe-pins")),
+            bookmarks_file: cli
+                .bookmarks_file
+                .clone()
+                .or_else(|| get_default_data_file_path(".tiny-fe-bookmarks")),
+            favorites_file: cli
+                .favorites_file
+                .clone()
+                .or_else(|| get_default_data_file_path(".tiny-fe-favorites")),
+            on_cd_command: cli.on_cd.clone(),
+            navigation_log_file: cli.log.clone(),
+            grid: cli.grid,
+            persist_search_on_cd: cli.keep_search,
+            default_query: cli.default_query.clone(),
+            slow_path_prefixes: cli.slow_paths.clone(),
+            permissions_format: cli.permissions,
+            show_owner: cli.owner,
+            command_palette_key: cli.palette_key,
+            enter_keys: cli.enter_keys.clone(),
+            wrap_siblings: cli.wrap_siblings,
+            show_parent_context: cli.parent_context,
+            header: cli.header.clone().unwrap_or_default(),
+            header_alignment: cli.header_align,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cli_is_converted_to_config_correctly() {
+        let cli = Cli::parse_from(["tiny-fe", "--confirm-exit", "300"]);
+        let config = Config::from(&cli);
+
+        assert_eq!(
+            config.exit_confirmation_duration,
+            Some(Duration::from_millis(300))
+        );
+
+        let cli = Cli::parse_from(["tiny-fe"]);
+        let config = Config::from(&cli);
+
+        assert_eq!(config.exit_confirmation_duration, None);
+        assert!(cli.command.is_none());
+        assert!(!config.hide_hidden_files);
+        assert!(config.always_show.is_empty());
+    }
+
+    #[test]
+    fn cli_parses_popd_subcommand() {
+        let cli = Cli::parse_from(["tiny-fe", "popd", "--stack-file", "/tmp/stack"]);
+
+        assert!(matches!(cli.command, Some(Command::Popd)));
+        assert_eq!(
+            cli.get_directory_stack_file(),
+            Some(PathBuf::from("/tmp/stack"))
+        );
+    }
+
+    #[test]
+    fn cli_always_show_is_comma_separated() {
+        let cli = Cli::parse_from(["tiny-fe", "--hide-hidden", "--always-show", ".git,.config"]);
+        let config = Config::from(&cli);
+
+        assert!(config.hide_hidden_files);
+        assert_eq!(config.always_show, vec![".git", ".config"]);
+    }
+
+    #[test]
+    fn cli_enter_keys_are_comma_separated() {
+        let cli = Cli::parse_from(["tiny-fe", "--enter-keys", ";,'"]);
+        let config = Config::from(&cli);
+
+        assert_eq!(config.enter_keys, vec![';', '\'']);
+    }
+
+    #[test]
+    fn cli_rejects_invalid_export_variable_names() {
+        let cli = Cli::parse_from(["tiny-fe", "--export-var", "TINY_DC_RESULT"]);
+        assert_eq!(cli.export_var, Some("TINY_DC_RESULT".into()));
+
+        assert!(Cli::try_parse_from(["tiny-fe", "--export-var", "MY-DIR"]).is_err());
+    }
+
+    #[test]
+    fn cli_parses_directory_grouping() {
+        let config = Config::from(&Cli::parse_from(["tiny-fe"]));
+        assert_eq!(config.directory_grouping, DirectoryGrouping::First);
+
+        let config = Config::from(&Cli::parse_from(["tiny-fe", "--directories", "mixed"]));
+        assert_eq!(config.directory_grouping, DirectoryGrouping::Mixed);
+    }
+
+    #[test]
+    fn cli_parses_header_content() {
+        let cli = Cli::parse_from(["tiny-fe", "--header", "path", "--header-align", "left"]);
+        let config = Config::from(&cli);
+
+        assert_eq!(config.header, HeaderContent::CurrentDirectory);
+        assert_eq!(config.header_alignment, HeaderAlignment::Left);
+
+        let cli = Cli::parse_from(["tiny-fe", "--header", "My files"]);
+        assert_eq!(
+            Config::from(&cli).header,
+            HeaderContent::Custom("My files".into())
+        );
+    }
+
+    #[test]
+    fn cli_parses_completions_subcommand() {
+        let cli = Cli::parse_from(["tiny-fe", "completions", "zsh"]);
+
+        assert!(matches!(
+            cli.command,
+            Some(Command::Completions { shell: Shell::Zsh })
+        ));
+    }
+
+    #[test]
+    fn cli_parses_init_subcommand() {
+        let cli = Cli::parse_from(["tiny-fe", "init", "fish"]);
+
+        assert!(matches!(
+            cli.command,
+            Some(Command::Init {
+                shell: IntegrationShell::Fish
+            })
+        ));
+
+        // Clap lists the supported shells in the error
+        let err = Cli::try_parse_from(["tiny-fe", "init", "nushell"]).unwrap_err();
+        assert!(err.to_string().contains("bash, zsh, fish"));
+    }
+
+    #[test]
+    fn generate_completions_works_for_supported_shells() {
+        for shell in [
+            Shell::Bash,
+            Shell::Zsh,
+            Shell::Fish,
+            Shell::PowerShell,
+            Shell::Elvish,
+        ] {
+            let mut output = Vec::new();
+            generate_completions(shell, &mut output);
+
+            let output = String::from_utf8(output).unwrap();
+            assert!(output.contains("tiny-fe"), "{shell} completions are empty");
+            assert!(output.contains("popd"), "{shell} completions miss popd");
+        }
+    }
+}
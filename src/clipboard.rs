@@ -0,0 +1,213 @@
+use std::{
+    fmt,
+    io::{self, Write},
+    path::Path,
+    process::{Command, Stdio},
+};
+
+use anyhow::Context;
+
+/// An abstraction over the system clipboard, so that the app can be tested without touching the
+/// user's real clipboard.
+pub trait Clipboard: fmt::Debug {
+    /// Replaces the contents of the clipboard with the given text.
+    fn set_text(&mut self, text: &str) -> anyhow::Result<()>;
+
+    /// Returns the text that is currently in the clipboard.
+    fn get_text(&mut self) -> anyhow::Result<String>;
+}
+
+/// The commands we try (in order) to write to the clipboard. The first one that runs successfully
+/// wins.
+const COPY_COMMANDS: [(&str, &[&str]); 5] = [
+    ("pbcopy", &[]),
+    ("wl-copy", &[]),
+    ("xclip", &["-selection", "clipboard"]),
+    ("xsel", &["--clipboard", "--input"]),
+    ("clip", &[]),
+];
+
+/// The commands we try (in order) to read the clipboard, the counterparts of `COPY_COMMANDS`.
+const PASTE_COMMANDS: [(&str, &[&str]); 5] = [
+    ("pbpaste", &[]),
+    ("wl-paste", &["--no-newline"]),
+    ("xclip", &["-selection", "clipboard", "-out"]),
+    ("xsel", &["--clipboard", "--output"]),
+    ("powershell", &["-NoProfile", "-Command", "Get-Clipboard"]),
+];
+
+/// A clipboard backed by the platform's clipboard utilities (`pbcopy`, `wl-copy`, `xclip`, `xsel`
+/// or `clip`). If none of them are available we fall back to the OSC 52 escape sequence, which
+/// most modern terminals (and terminal multiplexers) understand - this also makes copying work
+/// over SSH.
+#[derive(Debug, Default)]
+pub struct SystemClipboard;
+
+impl SystemClipboard {
+    fn run_copy_command(program: &str, args: &[&str], text: &str) -> anyhow::Result<()> {
+        let mut child = Command::new(program)
+            .args(args)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn()?;
+
+        child
+            .stdin
+            .take()
+            .context("Failed to open the clipboard command's stdin")?
+            .write_all(text.as_bytes())?;
+
+        let status = child.wait()?;
+        anyhow::ensure!(status.success(), "{program} exited with {status}");
+
+        Ok(())
+    }
+
+    fn run_paste_command(program: &str, args: &[&str]) -> anyhow::Result<String> {
+        let output = Command::new(program)
+            .args(args)
+            .stdin(Stdio::null())
+            .stderr(Stdio::null())
+            .output()?;
+
+        anyhow::ensure!(
+            output.status.success(),
+            "{program} exited with {}",
+            output.status
+        );
+
+        Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+    }
+}
+
+impl Clipboard for SystemClipboard {
+    fn set_text(&mut self, text: &str) -> anyhow::Result<()> {
+        for (program, args) in COPY_COMMANDS {
+            if Self::run_copy_command(program, args, text).is_ok() {
+                return Ok(());
+            }
+        }
+
+        // We render to stderr, so that's where the terminal is listening
+        let mut stderr = io::stderr();
+        stderr.write_all(osc52_sequence(text).as_bytes())?;
+        stderr.flush()?;
+
+        Ok(())
+    }
+
+    fn get_text(&mut self) -> anyhow::Result<String> {
+        // Unlike copying there's no fallback, few terminals allow reading the clipboard with
+        // OSC 52
+        PASTE_COMMANDS
+            .into_iter()
+            .find_map(|(program, args)| Self::run_paste_command(program, args).ok())
+            .context("No clipboard utility is available to read the clipboard")
+    }
+}
+
+/// Builds the OSC 52 escape sequence that asks the terminal to put the given text in the system
+/// clipboard.
+fn osc52_sequence(text: &str) -> String {
+    format!("\x1b]52;c;{}\x07", base64_encode(text.as_bytes()))
+}
+
+/// Builds the `file://` URI of the given (absolute) path, everything besides the unreserved
+/// characters and the separators is percent-encoded (non-ASCII characters byte by byte, as UTF-8).
+pub fn file_uri(path: &Path) -> String {
+    #[cfg(unix)]
+    let bytes = {
+        use std::os::unix::ffi::OsStrExt;
+        path.as_os_str().as_bytes().to_vec()
+    };
+
+    // Windows paths (`C:\dir`) become `/C:/dir`
+    #[cfg(not(unix))]
+    let bytes = {
+        let path = path.to_string_lossy().replace('\\', "/");
+        if path.starts_with('/') {
+            path.into_bytes()
+        } else {
+            format!("/{path}").into_bytes()
+        }
+    };
+
+    let mut result = String::from("file://");
+
+    for byte in bytes {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' | b'/' => {
+                result.push(byte as char)
+            }
+            _ => result.push_str(&format!("%{byte:02X}")),
+        }
+    }
+
+    result
+}
+
+fn base64_encode(bytes: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+    let mut result = String::with_capacity(bytes.len().div_ceil(3) * 4);
+
+    for chunk in bytes.chunks(3) {
+        let b = [
+            chunk[0],
+            chunk.get(1).copied().unwrap_or(0),
+            chunk.get(2).copied().unwrap_or(0),
+        ];
+        let n = (u32::from(b[0]) << 16) | (u32::from(b[1]) << 8) | u32::from(b[2]);
+
+        result.push(ALPHABET[(n >> 18) as usize & 63] as char);
+        result.push(ALPHABET[(n >> 12) as usize & 63] as char);
+
+        if chunk.len() > 1 {
+            result.push(ALPHABET[(n >> 6) as usize & 63] as char);
+        } else {
+            result.push('=');
+        }
+
+        if chunk.len() > 2 {
+            result.push(ALPHABET[n as usize & 63] as char);
+        } else {
+            result.push('=');
+        }
+    }
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn base64_encode_works_correctly() {
+        assert_eq!(base64_encode(b""), "");
+        assert_eq!(base64_encode(b"f"), "Zg==");
+        assert_eq!(base64_encode(b"fo"), "Zm8=");
+        assert_eq!(base64_encode(b"foo"), "Zm9v");
+        assert_eq!(base64_encode(b"/home/user"), "L2hvbWUvdXNlcg==");
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn file_uri_percent_encodes_spaces_and_unicode() {
+        assert_eq!(file_uri(Path::new("/home/user")), "file:///home/user");
+        assert_eq!(
+            file_uri(Path::new("/home/user/my notes/café.txt")),
+            "file:///home/user/my%20notes/caf%C3%A9.txt"
+        );
+        assert_eq!(
+            file_uri(Path::new("/tmp/a#b?c%d")),
+            "file:///tmp/a%23b%3Fc%25d"
+        );
+    }
+
+    #[test]
+    fn osc52_sequence_works_correctly() {
+        assert_eq!(osc52_sequence("foo"), "\x1b]52;c;Zm9v\x07");
+    }
+}
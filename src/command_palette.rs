@@ -0,0 +1,206 @@
+//! A filterable overlay that lists every action that is bound to a key in normal mode, together
+//! with its keys, so that the actions can be discovered (and run) without remembering the keys.
+
+use ratatui::{prelude::*, widgets::*};
+
+use crate::{
+    app::{Action, InputMode},
+    hotkeys::HotkeysRegistry,
+};
+
+/// An action that can be run from the command palette
+#[derive(Debug, Clone, PartialEq)]
+pub struct PaletteCommand {
+    pub action: Action,
+    pub description: &'static str,
+
+    /// The key sequences that are bound to the action, formatted for display (e.g. `g g`)
+    pub key_sequences: Vec<String>,
+}
+
+#[derive(Debug, Default)]
+pub struct CommandPalette {
+    /// The query the commands are filtered by
+    input: String,
+
+    /// All the commands, sorted by their description
+    commands: Vec<PaletteCommand>,
+
+    list_state: ListState,
+}
+
+impl CommandPalette {
+    /// Builds the palette from the normal mode hotkeys of the registry, actions without a
+    /// description (the ones that don't make sense on their own) are left out.
+    pub fn new(hotkeys_registry: &HotkeysRegistry<InputMode, Action>) -> Self {
+        let mut commands: Vec<PaletteCommand> = Vec::new();
+
+        for (key_combos, &action) in hotkeys_registry.get_system_hotkeys(&InputMode::Normal) {
+            let Some(description) = action.get_description() else {
+                continue;
+            };
+
+            let key_sequence = key_combos
+                .iter()
+                .map(|x| x.to_string())
+                .collect::<Vec<_>>()
+                .join(" ");
+
+            match commands.iter_mut().find(|x| x.action == action) {
+                Some(command) => command.key_sequences.push(key_sequence),
+                None => commands.push(PaletteCommand {
+                    action,
+                    description,
+                    key_sequences: vec![key_sequence],
+                }),
+            }
+        }
+
+        // The registry doesn't keep the order of the hotkeys, so we sort everything to keep the
+        // palette stable
+        for command in commands.iter_mut() {
+            command.key_sequences.sort();
+        }
+
+        commands.sort_by_key(|x| x.description);
+
+        CommandPalette {
+            commands,
+            ..Default::default()
+        }
+    }
+
+    pub fn get_filtered_commands(&self) -> Vec<&PaletteCommand> {
+        let query = self.input.to_lowercase();
+
+        self.commands
+            .iter()
+            .filter(|x| x.description.to_lowercase().contains(&query))
+            .collect()
+    }
+
+    pub fn push(&mut self, c: char) {
+        self.input.push(c);
+        self.list_state = ListState::default();
+    }
+
+    pub fn pop(&mut self) {
+        self.input.pop();
+        self.list_state = ListState::default();
+    }
+
+    pub fn select_next(&mut self) {
+        let last_index = self.get_filtered_commands().len().saturating_sub(1);
+        let index = self.list_state.selected().unwrap_or_default() + 1;
+
+        self.list_state.select(Some(index.min(last_index)));
+    }
+
+    pub fn select_previous(&mut self) {
+        let index = self.list_state.selected().unwrap_or_default();
+        self.list_state.select(Some(index.saturating_sub(1)));
+    }
+
+    /// Returns the action of the selected command (the first one when nothing is selected yet)
+    pub fn get_selected_action(&self) -> Option<Action> {
+        let index = self.list_state.selected().unwrap_or_default();
+
+        self.get_filtered_commands().get(index).map(|x| x.action)
+    }
+
+    pub fn render(&mut self, buf: &mut Buffer) {
+        let size = buf.area();
+
+        let popup_area = Rect {
+            x: size.width / 4,
+            y: size.height / 4,
+            width: size.width / 2,
+            height: size.height / 2,
+        };
+
+        let block = Block::default()
+            .title(" Commands ")
+            .title_style(Style::default().bold().fg(Color::Yellow))
+            .borders(Borders::ALL)
+            .border_type(BorderType::Plain);
+
+        let inner_area = block.inner(popup_area);
+
+        Clear.render(popup_area, buf);
+        block.render(popup_area, buf);
+
+        let [input_area, list_area] =
+            Layout::vertical([Constraint::Length(1), Constraint::Fill(1)]).areas(inner_area);
+
+        Paragraph::new(format!(":{}", self.input))
+            .style(Style::default().fg(Color::Yellow))
+            .render(input_area, buf);
+
+        let items: Vec<ListItem> = self
+            .get_filtered_commands()
+            .into_iter()
+            .map(|x| {
+                ListItem::new(Line::from(vec![
+                    Span::raw(x.description),
+                    Span::styled(
+                        format!("  {}", x.key_sequences.join(", ")),
+                        Style::default().dark_gray(),
+                    ),
+                ]))
+            })
+            .collect();
+
+        let list = List::new(items)
+            .highlight_style(Style::new().bg(Color::Gray).fg(Color::Black))
+            .highlight_symbol(">")
+            .highlight_spacing(HighlightSpacing::Always);
+
+        if self.list_state.selected().is_none() {
+            self.list_state.select_first();
+        }
+
+        StatefulWidget::render(list, list_area, buf, &mut self.list_state);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn command_palette_groups_keys_by_action_and_filters_by_description() {
+        let mut palette = CommandPalette::new(&HotkeysRegistry::new_with_default_system_hotkeys());
+
+        let select_first = palette
+            .commands
+            .iter()
+            .find(|x| x.action == Action::SelectFirst)
+            .unwrap();
+        assert_eq!(select_first.key_sequences, vec!["Home", "g g"]);
+
+        // Actions that need an argument from the key (like the quick-jump keys) aren't listed
+        assert!(palette
+            .commands
+            .iter()
+            .all(|x| !matches!(x.action, Action::ChangeDirectoryToEntryWithIndex(_))));
+
+        for c in "HIDDEN".chars() {
+            palette.push(c);
+        }
+
+        let descriptions: Vec<&str> = palette
+            .get_filtered_commands()
+            .iter()
+            .map(|x| x.description)
+            .collect();
+        assert_eq!(descriptions, vec!["Toggle hidden files"]);
+        assert_eq!(
+            palette.get_selected_action(),
+            Some(Action::ToggleHiddenFiles)
+        );
+
+        palette.pop();
+        palette.push('x');
+        assert_eq!(palette.get_selected_action(), None);
+    }
+}
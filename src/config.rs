@@ -0,0 +1,227 @@
+use std::{env, path::PathBuf, time::Duration};
+
+use crate::{app::ListMode, theme::Theme};
+
+/// The configuration of the application, it controls the optional behaviours of the app and is
+/// normally built from the command line arguments.
+#[derive(Debug, Clone, Default)]
+pub struct Config {
+    /// The list mode the app starts in
+    pub start_list_mode: ListMode,
+
+    /// The styles used when rendering the app
+    pub theme: Theme,
+
+    /// Whether the names are sorted in descending order (directories are still grouped according to
+    /// `directory_grouping` and pinned entries stay on top)
+    pub sort_descending: bool,
+
+    /// Where the directories are listed relative to the files
+    pub directory_grouping: DirectoryGrouping,
+
+    /// When set, the path that is about to be returned is shown in the footer for the given
+    /// duration before the app exits. This is useful for verifying what the shell integration will
+    /// receive.
+    pub exit_confirmation_duration: Option<Duration>,
+
+    /// Whether entries starting with a `.` should be hidden from the directory listing
+    pub hide_hidden_files: bool,
+
+    /// Names of hidden entries that should always be shown, even when hidden files are hidden (for
+    /// example `.git` or `.config`)
+    pub always_show: Vec<String>,
+
+    /// Whether the entries in `always_show` should be sorted to the top of the listing
+    pub always_show_on_top: bool,
+
+    /// The maximum width (in terminal columns) of a displayed entry name, longer names are
+    /// truncated with an ellipsis and the full name of the selected entry is shown in the footer
+    pub max_name_width: Option<usize>,
+
+    /// Whether the terminal title should follow the current directory (opt-in, since some
+    /// terminals and multiplexers don't handle it well)
+    pub set_terminal_title: bool,
+
+    /// When set, entering a directory with more entries than the threshold asks for a
+    /// confirmation first, since listing huge directories can momentarily hang the app
+    pub large_directory_threshold: Option<usize>,
+
+    /// When set, the confirmations are cancelled if they aren't answered within the duration (the
+    /// time left is shown in the confirmation)
+    pub confirmation_timeout: Option<Duration>,
+
+    /// When set, the app exits by itself (as if it was quit) once no key has been pressed for the
+    /// duration, so that it isn't left running in an unattended terminal
+    pub idle_timeout: Option<Duration>,
+
+    /// Whether to render `ls -F` style indicators after the names (`@` for symlinks, `*` for
+    /// executables)
+    pub classify: bool,
+
+    /// Whether the `/` that's normally rendered after the names of directories is left out
+    pub hide_directory_slash: bool,
+
+    /// Whether the quick-jump keys are left out of the listing, they still work when hidden
+    pub hide_quick_jump_hints: bool,
+
+    /// When set, the search stops after collecting the given number of matches, which keeps the
+    /// filtering cheap in huge directories
+    pub max_search_results: Option<usize>,
+
+    /// When set, a search that narrows the listing down to a single directory acts on it
+    /// automatically once the user pauses typing
+    pub auto_enter_single: Option<AutoEnterSingle>,
+
+    /// The file used for the directory stack (see `dir_stack`)
+    pub directory_stack_file: Option<PathBuf>,
+
+    /// The file the pinned entries are stored in (see `pins`)
+    pub pins_file: Option<PathBuf>,
+
+    /// The file the bookmarked directories are stored in (see `bookmarks`)
+    pub bookmarks_file: Option<PathBuf>,
+
+    /// The file the numbered directory slots are stored in (see `favorites`)
+    pub favorites_file: Option<PathBuf>,
+
+    /// A command that is spawned (without waiting for it) with the new path as its last argument
+    /// every time the current directory changes
+    pub on_cd_command: Option<String>,
+
+    /// The file every visited directory is appended to (see `navigation_log`)
+    pub navigation_log_file: Option<PathBuf>,
+
+    /// Whether the entries are rendered in columns (like `ls`) when they're short enough to fit in
+    /// more than one column
+    pub grid: bool,
+
+    /// Whether the search query is kept (and applied again) when the current directory changes,
+    /// which is handy for filtering by the same term at each level
+    pub persist_search_on_cd: bool,
+
+    /// A search query that is applied as soon as the app starts, it can be cleared like any other
+    /// query
+    pub default_query: Option<String>,
+
+    /// Directories under these prefixes (e.g. network mounts) are listed without waiting for the
+    /// metadata of their entries, which is read in the background instead
+    pub slow_path_prefixes: Vec<PathBuf>,
+
+    /// When set, the Unix permissions of the entries are shown in front of their names in the
+    /// given format (there's nothing to show on other platforms)
+    pub permissions_format: Option<PermissionsFormat>,
+
+    /// Whether the owners (`user:group`) of the entries are shown in front of their names (there's
+    /// nothing to show on non-Unix platforms)
+    pub show_owner: bool,
+
+    /// An extra key (besides `:`) that opens the command palette
+    pub command_palette_key: Option<char>,
+
+    /// Extra keys (besides `Enter`, `l` and `→`) that enter the selected entry
+    pub enter_keys: Vec<char>,
+
+    /// Whether going to the next directory next to the current one (`}`) wraps around to the
+    /// first one at the end, and vice versa
+    pub wrap_siblings: bool,
+
+    /// Whether a line with the parent directory is shown above the directory listing
+    pub show_parent_context: bool,
+
+    /// What's shown in the header at the top of the app
+    pub header: HeaderContent,
+
+    /// Where the header is placed horizontally
+    pub header_alignment: HeaderAlignment,
+}
+
+/// What to do with the only directory left by a search, see `Config::auto_enter_single`
+#[derive(Debug, Clone, Copy, PartialEq, clap::ValueEnum)]
+pub enum AutoEnterSingle {
+    /// Go into the directory and keep navigating
+    Enter,
+    /// Exit with the directory, as if it had been entered and the app quit
+    Exit,
+}
+
+/// How the permissions of the entries are shown, see `Config::permissions_format`
+#[derive(Debug, Clone, Copy, PartialEq, clap::ValueEnum)]
+pub enum PermissionsFormat {
+    /// Like `ls -l`, e.g. `rwxr-xr-x`
+    Symbolic,
+    /// In octal, e.g. `755`
+    Octal,
+}
+
+/// What's shown in the header, see `Config::header`
+#[derive(Debug, Clone, PartialEq, Default)]
+pub enum HeaderContent {
+    /// The name and the version of the app
+    #[default]
+    AppVersion,
+    /// The directory that is being listed
+    CurrentDirectory,
+    /// The list mode (the directory listing or the most accessed paths)
+    ListMode,
+    /// A custom text
+    Custom(String),
+}
+
+/// Parses the content of the header, `version`, `path` and `mode` are the built-in contents and
+/// anything else is shown as is.
+pub fn parse_header_content(value: &str) -> anyhow::Result<HeaderContent> {
+    let content = match value {
+        "version" => HeaderContent::AppVersion,
+        "path" => HeaderContent::CurrentDirectory,
+        "mode" => HeaderContent::ListMode,
+        text => HeaderContent::Custom(text.to_string()),
+    };
+
+    Ok(content)
+}
+
+/// Where the header is placed, see `Config::header_alignment`
+#[derive(Debug, Clone, Copy, PartialEq, Default, clap::ValueEnum)]
+pub enum HeaderAlignment {
+    Left,
+    #[default]
+    Center,
+    Right,
+}
+
+/// Where the directories are listed relative to the files, see `Config::directory_grouping`
+#[derive(Debug, Clone, Copy, PartialEq, Default, clap::ValueEnum)]
+pub enum DirectoryGrouping {
+    /// The directories are listed before the files
+    #[default]
+    First,
+    /// The directories are listed after the files
+    Last,
+    /// The directories and the files are sorted together by name
+    Mixed,
+}
+
+impl DirectoryGrouping {
+    /// The grouping that comes after this one when cycling through them
+    pub fn next(self) -> Self {
+        match self {
+            DirectoryGrouping::First => DirectoryGrouping::Last,
+            DirectoryGrouping::Last => DirectoryGrouping::Mixed,
+            DirectoryGrouping::Mixed => DirectoryGrouping::First,
+        }
+    }
+}
+
+/// Returns the user's home directory, if it can be determined.
+pub fn get_home_directory() -> Option<PathBuf> {
+    env::var_os("HOME")
+        .or_else(|| env::var_os("USERPROFILE"))
+        .filter(|x| !x.is_empty())
+        .map(PathBuf::from)
+}
+
+/// Returns the default location of a data file (for example the directory stack), data files live
+/// in the user's home directory.
+pub fn get_default_data_file_path(file_name: &str) -> Option<PathBuf> {
+    get_home_directory().map(|x| x.join(file_name))
+}
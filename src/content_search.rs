@@ -0,0 +1,98 @@
+//! A "grep-lite" that looks for the search query inside the files of the current directory, so
+//! that a file can be found by a phrase it contains. It's opt-in per query (`Ctrl + g` while
+//! searching) since it has to read the files, and only small text files are looked at.
+
+use std::{fs, path::Path};
+
+/// Files that are bigger than this are skipped
+pub const MAX_FILE_SIZE: u64 = 64 * 1024;
+
+/// The maximum number of files that are read for a single query, the search runs on every key
+/// press so a big directory can't be read as a whole (at most ~12 MiB are read this way)
+pub const MAX_FILES_PER_QUERY: usize = 200;
+
+/// The maximum number of characters of the matching line that are kept for display
+const MAX_SNIPPET_LENGTH: usize = 60;
+
+/// Looks for the (normalized, lowercase) query in the file and returns the first matching line,
+/// trimmed and shortened, as a snippet. Returns `None` for files that are too big, binary or that
+/// can't be read.
+pub fn find_snippet<T: AsRef<Path>>(path: T, query: &str) -> Option<String> {
+    let path = path.as_ref();
+
+    if query.is_empty() || fs::metadata(path).ok()?.len() > MAX_FILE_SIZE {
+        return None;
+    }
+
+    let contents = fs::read(path).ok()?;
+
+    // A NUL byte is a good enough hint that the file isn't text
+    if contents.contains(&0) {
+        return None;
+    }
+
+    let contents = String::from_utf8(contents).ok()?;
+    let line = contents
+        .lines()
+        .find(|x| x.to_lowercase().contains(query))?
+        .trim();
+
+    let mut snippet: String = line.chars().take(MAX_SNIPPET_LENGTH).collect();
+
+    if line.chars().count() > MAX_SNIPPET_LENGTH {
+        snippet.push('…');
+    }
+
+    Some(snippet)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn find_snippet_returns_first_matching_line() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let path = temp_dir.path().join("notes.txt");
+        fs::write(
+            &path,
+            "# Notes\n\n   The quick brown Fox jumps  \nAnother fox\n",
+        )
+        .unwrap();
+
+        assert_eq!(
+            find_snippet(&path, "fox"),
+            Some("The quick brown Fox jumps".into())
+        );
+        assert_eq!(find_snippet(&path, "wolf"), None);
+    }
+
+    #[test]
+    fn find_snippet_shortens_long_lines() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let path = temp_dir.path().join("long.txt");
+        fs::write(&path, format!("needle {}", "x".repeat(100))).unwrap();
+
+        let snippet = find_snippet(&path, "needle").unwrap();
+
+        assert_eq!(snippet.chars().count(), MAX_SNIPPET_LENGTH + 1);
+        assert!(snippet.ends_with('…'));
+    }
+
+    #[test]
+    fn find_snippet_skips_binary_and_big_files() {
+        let temp_dir = tempfile::tempdir().unwrap();
+
+        let binary_path = temp_dir.path().join("binary.bin");
+        fs::write(&binary_path, b"needle\0\x01\x02").unwrap();
+        assert_eq!(find_snippet(&binary_path, "needle"), None);
+
+        let big_path = temp_dir.path().join("big.txt");
+        fs::write(
+            &big_path,
+            format!("needle\n{}", "x".repeat(MAX_FILE_SIZE as usize)),
+        )
+        .unwrap();
+        assert_eq!(find_snippet(&big_path, "needle"), None);
+    }
+}
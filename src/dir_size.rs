@@ -0,0 +1,205 @@
+//! Computes the total size of a directory in the background. Walking a big tree can take a long
+//! time, so the size is only computed when asked for, the walk can be cancelled and it gives up
+//! after a while (the size computed so far is reported as partial then).
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        mpsc::{self, Receiver},
+        Arc,
+    },
+    thread,
+    time::{Duration, Instant},
+};
+
+/// The result of walking a directory
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct DirectorySize {
+    /// The total size of the files in bytes, symlinks count with their own size
+    pub bytes: u64,
+
+    /// The number of files (and symlinks) that were counted
+    pub files: u64,
+
+    /// The number of entries that couldn't be read (for example because of their permissions)
+    pub skipped: u64,
+
+    /// Whether the walk stopped before going through the whole tree
+    pub is_partial: bool,
+}
+
+/// Walks the directory at the given path and sums up the sizes of the files in it. Entries that
+/// can't be read are skipped, the walk stops early once `deadline` has passed or `cancelled` is
+/// set. Symlinks aren't followed, so that a link cycle can't keep the walk going.
+pub fn compute_directory_size(
+    path: &Path,
+    deadline: Instant,
+    cancelled: &AtomicBool,
+) -> DirectorySize {
+    let mut size = DirectorySize::default();
+    let mut pending = vec![path.to_path_buf()];
+
+    while let Some(directory) = pending.pop() {
+        if cancelled.load(Ordering::Relaxed) || Instant::now() >= deadline {
+            size.is_partial = true;
+            break;
+        }
+
+        let Ok(entries) = fs::read_dir(&directory) else {
+            size.skipped += 1;
+            continue;
+        };
+
+        for entry in entries {
+            // The metadata of a directory entry doesn't follow symlinks
+            let Ok((path, metadata)) = entry.and_then(|x| Ok((x.path(), x.metadata()?))) else {
+                size.skipped += 1;
+                continue;
+            };
+
+            if metadata.is_dir() {
+                pending.push(path);
+            } else {
+                size.bytes += metadata.len();
+                size.files += 1;
+            }
+        }
+    }
+
+    size
+}
+
+/// A directory size that is being computed on a background thread
+#[derive(Debug)]
+pub struct DirectorySizeCalculation {
+    /// The directory that is being walked
+    pub path: PathBuf,
+
+    receiver: Receiver<DirectorySize>,
+
+    /// Tells the background thread to stop walking
+    cancelled: Arc<AtomicBool>,
+}
+
+impl DirectorySizeCalculation {
+    /// Starts computing the size of the given directory on a background thread, giving up after
+    /// the given time.
+    pub fn spawn(path: PathBuf, timeout: Duration) -> Self {
+        let (sender, receiver) = mpsc::channel();
+        let cancelled = Arc::new(AtomicBool::new(false));
+        let deadline = Instant::now() + timeout;
+
+        thread::spawn({
+            let path = path.clone();
+            let cancelled = cancelled.clone();
+
+            move || {
+                let size = compute_directory_size(&path, deadline, &cancelled);
+
+                // The receiver is gone when the calculation has been cancelled
+                let _ = sender.send(size);
+            }
+        });
+
+        DirectorySizeCalculation {
+            path,
+            receiver,
+            cancelled,
+        }
+    }
+
+    /// Returns the size once it has been computed, without waiting for it. Returns `None` while
+    /// it's still being computed.
+    pub fn try_receive(&self) -> Option<DirectorySize> {
+        self.receiver.try_recv().ok()
+    }
+}
+
+impl Drop for DirectorySizeCalculation {
+    fn drop(&mut self) {
+        // The thread would otherwise keep walking for nothing
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+}
+
+/// Formats the size in bytes the way people read it, e.g. `1.5 MiB`
+pub fn format_size(bytes: u64) -> String {
+    const UNITS: [&str; 5] = ["KiB", "MiB", "GiB", "TiB", "PiB"];
+
+    if bytes < 1024 {
+        return format!("{bytes} B");
+    }
+
+    let mut value = bytes as f64 / 1024.0;
+    let mut unit = UNITS[0];
+
+    for next_unit in UNITS.iter().skip(1) {
+        if value < 1024.0 {
+            break;
+        }
+
+        value /= 1024.0;
+        unit = next_unit;
+    }
+
+    format!("{value:.1} {unit}")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn compute_directory_size_sums_up_nested_files() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let nested = temp_dir.path().join("a").join("b");
+
+        fs::create_dir_all(&nested).unwrap();
+        fs::write(temp_dir.path().join("one"), [0; 100]).unwrap();
+        fs::write(temp_dir.path().join("a").join("two"), [0; 2000]).unwrap();
+        fs::write(nested.join("three"), [0; 30]).unwrap();
+
+        let size = compute_directory_size(
+            temp_dir.path(),
+            Instant::now() + Duration::from_secs(60),
+            &AtomicBool::new(false),
+        );
+
+        assert_eq!(
+            size,
+            DirectorySize {
+                bytes: 2130,
+                files: 3,
+                skipped: 0,
+                is_partial: false,
+            }
+        );
+    }
+
+    #[test]
+    fn compute_directory_size_stops_when_cancelled() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        fs::write(temp_dir.path().join("one"), [0; 100]).unwrap();
+
+        let size = compute_directory_size(
+            temp_dir.path(),
+            Instant::now() + Duration::from_secs(60),
+            &AtomicBool::new(true),
+        );
+
+        assert!(size.is_partial);
+        assert_eq!(size.bytes, 0);
+    }
+
+    #[test]
+    fn format_size_works_correctly() {
+        assert_eq!(format_size(0), "0 B");
+        assert_eq!(format_size(1023), "1023 B");
+        assert_eq!(format_size(1024), "1.0 KiB");
+        assert_eq!(format_size(1536), "1.5 KiB");
+        assert_eq!(format_size(5 * 1024 * 1024), "5.0 MiB");
+        assert_eq!(format_size(3 * 1024 * 1024 * 1024), "3.0 GiB");
+    }
+}
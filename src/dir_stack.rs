@@ -0,0 +1,112 @@
+//! A directory stack that allows the app to cooperate with the shell's `pushd`/`popd`.
+//!
+//! Inside the app, pressing `M` pushes the current directory onto the stack (a plain text file
+//! with one path per line, the most recent one last). Outside of the app, `tiny-fe popd` prints
+//! and removes the most recent directory, so a small shell wrapper can jump back to it:
+//!
+//! ```sh
+//! tf-popd() {
+//!     local dir
+//!     dir="$(tiny-fe popd)" && [ -n "$dir" ] && pushd "$dir"
+//! }
+//! ```
+
+use std::path::{Path, PathBuf};
+
+use crate::path_lines::{read_path_lines, write_path_lines};
+
+#[derive(Debug)]
+pub struct DirectoryStack {
+    /// The file the stack is persisted to
+    file_path: PathBuf,
+
+    /// The directories in the stack, ordered from the oldest to the most recent
+    entries: Vec<PathBuf>,
+}
+
+impl DirectoryStack {
+    /// Loads the stack from the given file, a missing file is treated as an empty stack.
+    pub fn load_from_disk<T: AsRef<Path>>(file_path: T) -> anyhow::Result<Self> {
+        let file_path = file_path.as_ref().to_path_buf();
+        let entries = read_path_lines(&file_path)?;
+
+        Ok(DirectoryStack { file_path, entries })
+    }
+
+    pub fn save_to_disk(&self) -> anyhow::Result<()> {
+        write_path_lines(&self.file_path, &self.entries)
+    }
+
+    /// Pushes the directory on top of the stack and persists the stack.
+    pub fn push<T: AsRef<Path>>(&mut self, path: T) -> anyhow::Result<()> {
+        self.entries.push(path.as_ref().to_path_buf());
+        self.save_to_disk()
+    }
+
+    /// Removes the directory on top of the stack and persists the stack, returns `None` if the
+    /// stack is empty.
+    pub fn pop(&mut self) -> anyhow::Result<Option<PathBuf>> {
+        let entry = self.entries.pop();
+
+        if entry.is_some() {
+            self.save_to_disk()?;
+        }
+
+        Ok(entry)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use super::*;
+
+    #[test]
+    fn directory_stack_pops_in_reverse_push_order() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let file_path = temp_dir.path().join("stack");
+
+        let mut stack = DirectoryStack::load_from_disk(&file_path).unwrap();
+        stack.push("/home/user/first").unwrap();
+        stack.push("/home/user/second").unwrap();
+
+        assert_eq!(
+            stack.pop().unwrap(),
+            Some(PathBuf::from("/home/user/second"))
+        );
+        assert_eq!(
+            stack.pop().unwrap(),
+            Some(PathBuf::from("/home/user/first"))
+        );
+        assert_eq!(stack.pop().unwrap(), None);
+    }
+
+    #[test]
+    fn directory_stack_is_persisted_between_loads() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let file_path = temp_dir.path().join("stack");
+
+        let mut stack = DirectoryStack::load_from_disk(&file_path).unwrap();
+        stack.push("/home/user/first").unwrap();
+        stack.push("/home/user/second").unwrap();
+
+        assert_eq!(
+            fs::read_to_string(&file_path).unwrap(),
+            "/home/user/first\n/home/user/second\n"
+        );
+
+        let mut stack = DirectoryStack::load_from_disk(&file_path).unwrap();
+        assert_eq!(
+            stack.pop().unwrap(),
+            Some(PathBuf::from("/home/user/second"))
+        );
+
+        let mut stack = DirectoryStack::load_from_disk(&file_path).unwrap();
+        assert_eq!(
+            stack.pop().unwrap(),
+            Some(PathBuf::from("/home/user/first"))
+        );
+        assert_eq!(fs::read_to_string(&file_path).unwrap(), "");
+    }
+}
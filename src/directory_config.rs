@@ -0,0 +1,86 @@
+//! Directory-local configuration, like `.editorconfig` for the browser. A directory can contain a
+//! `.tiny-fe.toml` file that overrides how that directory (and only that directory) is listed:
+//!
+//! ```toml
+//! sort-descending = true
+//! hide-hidden = false
+//! ignore = ["target", "node_modules"]
+//! ```
+
+use std::{fs, io::ErrorKind, path::Path};
+
+use anyhow::Context;
+use serde::Deserialize;
+
+/// The name of the file a directory's config is read from
+pub const FILE_NAME: &str = ".tiny-fe.toml";
+
+#[derive(Debug, Default, Clone, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields, rename_all = "kebab-case")]
+pub struct DirectoryConfig {
+    /// Overrides `Config::sort_descending`
+    pub sort_descending: Option<bool>,
+
+    /// Overrides `Config::hide_hidden_files`
+    pub hide_hidden: Option<bool>,
+
+    /// The names of the entries that aren't listed at all
+    pub ignore: Vec<String>,
+}
+
+impl DirectoryConfig {
+    /// Loads the config of the given directory, returns `None` when the directory has none.
+    pub fn load<T: AsRef<Path>>(directory: T) -> anyhow::Result<Option<Self>> {
+        let file_path = directory.as_ref().join(FILE_NAME);
+
+        let contents = match fs::read_to_string(&file_path) {
+            Ok(contents) => contents,
+            Err(err) if err.kind() == ErrorKind::NotFound => return Ok(None),
+            Err(err) => return Err(err.into()),
+        };
+
+        let config = toml::from_str(&contents)
+            .with_context(|| format!("Failed to parse {}", file_path.display()))?;
+
+        Ok(Some(config))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn directory_config_loads_overrides() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        fs::write(
+            temp_dir.path().join(FILE_NAME),
+            "sort-descending = true\nignore = [\"target\"]\n",
+        )
+        .unwrap();
+
+        assert_eq!(
+            DirectoryConfig::load(temp_dir.path()).unwrap(),
+            Some(DirectoryConfig {
+                sort_descending: Some(true),
+                hide_hidden: None,
+                ignore: vec!["target".into()],
+            })
+        );
+    }
+
+    #[test]
+    fn directory_config_is_none_without_file() {
+        let temp_dir = tempfile::tempdir().unwrap();
+
+        assert_eq!(DirectoryConfig::load(temp_dir.path()).unwrap(), None);
+    }
+
+    #[test]
+    fn directory_config_rejects_unknown_settings() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        fs::write(temp_dir.path().join(FILE_NAME), "sort = \"size\"\n").unwrap();
+
+        assert!(DirectoryConfig::load(temp_dir.path()).is_err());
+    }
+}
@@ -1,11 +1,15 @@
 use std::{
+    collections::HashMap,
     fs::{DirEntry, ReadDir},
-    path::PathBuf,
+    io::{self, BufRead},
+    path::{Path, PathBuf},
 };
 
 use ratatui::{prelude::*, widgets::*};
+use unicode_normalization::UnicodeNormalization;
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
 
-use crate::hotkeys::KeyCombo;
+use crate::{config::PermissionsFormat, content_search, hotkeys::KeyCombo, theme::Theme};
 
 #[derive(Debug, PartialEq)]
 pub enum EntryKind {
@@ -17,39 +21,198 @@ pub enum EntryKind {
 pub struct Entry {
     pub path: PathBuf,
     pub kind: EntryKind,
+
+    /// The name of the entry, normalized to NFC so that it can be matched against the search query
+    /// regardless of how the file system composes it (macOS, for example, hands out NFD names)
     pub name: String,
+
+    /// The lowercased `name`, it's computed once so that searching and sorting don't have to
+    /// lowercase the names over and over
+    pub name_lower: String,
+
+    /// Whether the entry itself is a symbolic link
+    pub is_symlink: bool,
+
+    /// Whether the entry is a regular file with an executable bit set (always false on non-Unix
+    /// platforms)
+    pub is_executable: bool,
+
+    /// The Unix mode bits of the entry (`None` on other platforms and for entries that don't live
+    /// on the file system)
+    pub permissions: Option<u32>,
+
+    /// The ids of the user and group that own the entry (`None` on other platforms and for
+    /// entries that don't live on the file system)
+    pub owner_ids: Option<(u32, u32)>,
+}
+
+impl Entry {
+    /// Returns true if the entry is hidden (its name starts with a `.`)
+    pub fn is_hidden(&self) -> bool {
+        self.name.starts_with('.')
+    }
+
+    /// Returns the path of the entry relative to the given base, if the entry isn't located under
+    /// the base we fall back to the absolute path.
+    pub fn path_relative_to<T: AsRef<Path>>(&self, base: T) -> &Path {
+        self.path.strip_prefix(base).unwrap_or(&self.path)
+    }
+
+    /// Returns the `ls -F` style indicator of the entry: `@` for symlinks and `*` for executables.
+    /// Directories don't get one here since their `/` is rendered with the name (unless it's
+    /// hidden).
+    pub fn get_type_indicator(&self) -> Option<char> {
+        if self.is_symlink {
+            Some('@')
+        } else if self.is_executable {
+            Some('*')
+        } else {
+            None
+        }
+    }
 }
 
+/// The mode and the owner ids (uid, gid) of an entry
+pub type UnixMetadata = (u32, (u32, u32));
+
 impl TryFrom<DirEntry> for Entry {
     type Error = anyhow::Error;
 
     fn try_from(value: DirEntry) -> Result<Self, Self::Error> {
+        Entry::from_dir_entry(value, true)
+    }
+}
+
+impl Entry {
+    /// Creates the entry from the directory entry, reading its metadata is optional since it can
+    /// be slow (the kind of the entry is known without it on most platforms).
+    pub fn from_dir_entry(value: DirEntry, read_metadata: bool) -> anyhow::Result<Self> {
         let file_type = value.file_type()?;
         let path = value.path();
-        let name = path
+        let name: String = path
             .file_name()
             .unwrap_or_default()
             .to_string_lossy()
-            .into_owned();
+            .nfc()
+            .collect();
+        let name_lower = name.to_lowercase();
+
+        let is_symlink = file_type.is_symlink();
+        let metadata = if read_metadata {
+            get_unix_metadata(&value)?
+        } else {
+            None
+        };
+        let permissions = metadata.map(|(mode, _)| mode);
+        let owner_ids = metadata.map(|(_, owner_ids)| owner_ids);
 
         let item = if file_type.is_dir() {
             Entry {
                 path,
                 kind: EntryKind::Directory,
                 name,
+                name_lower,
+                is_symlink,
+                is_executable: false,
+                permissions,
+                owner_ids,
             }
         } else {
-            let extension = path.extension().map(|x| x.to_string_lossy().into_owned());
+            let extension = get_extension(&path);
+            let is_executable = file_type.is_file() && permissions.is_some_and(|x| x & 0o111 != 0);
 
             Entry {
                 path,
                 kind: EntryKind::File { extension },
                 name,
+                name_lower,
+                is_symlink,
+                is_executable,
+                permissions,
+                owner_ids,
             }
         };
 
         Ok(item)
     }
+
+    /// Sets the metadata that has been read after the entry was created
+    pub fn set_unix_metadata(&mut self, metadata: Option<UnixMetadata>) {
+        self.permissions = metadata.map(|(mode, _)| mode);
+        self.owner_ids = metadata.map(|(_, owner_ids)| owner_ids);
+        self.is_executable = matches!(self.kind, EntryKind::File { .. })
+            && !self.is_symlink
+            && self.permissions.is_some_and(|x| x & 0o111 != 0);
+    }
+}
+
+/// Brings the search query to the same form as the entry names (NFC), our search is case
+/// insensitive so the query is lowercased as well.
+fn normalize_search_query<T: AsRef<str>>(query: T) -> String {
+    query.as_ref().nfc().collect::<String>().to_lowercase()
+}
+
+/// The prefix of a search query that restricts the matches to directories (e.g. `d:src`)
+const DIRECTORIES_ONLY_PREFIX: &str = "d:";
+
+/// Normalizes the query and strips the `d:` prefix off of it, returns the text to match and
+/// whether only directories should match.
+fn parse_search_query<T: AsRef<str>>(query: T) -> (String, bool) {
+    let query = normalize_search_query(query);
+
+    match query.strip_prefix(DIRECTORIES_ONLY_PREFIX) {
+        Some(text) => (text.to_string(), true),
+        None => (query, false),
+    }
+}
+
+#[cfg(unix)]
+fn get_unix_metadata(entry: &DirEntry) -> anyhow::Result<Option<UnixMetadata>> {
+    use std::os::unix::fs::MetadataExt;
+
+    let metadata = entry.metadata()?;
+
+    Ok(Some((metadata.mode(), (metadata.uid(), metadata.gid()))))
+}
+
+#[cfg(not(unix))]
+fn get_unix_metadata(_entry: &DirEntry) -> anyhow::Result<Option<UnixMetadata>> {
+    Ok(None)
+}
+
+/// Reads the metadata of the path (without following symlinks, like `DirEntry::metadata`)
+#[cfg(unix)]
+pub fn read_unix_metadata(path: &Path) -> Option<UnixMetadata> {
+    use std::os::unix::fs::MetadataExt;
+
+    let metadata = std::fs::symlink_metadata(path).ok()?;
+
+    Some((metadata.mode(), (metadata.uid(), metadata.gid())))
+}
+
+#[cfg(not(unix))]
+pub fn read_unix_metadata(_path: &Path) -> Option<UnixMetadata> {
+    None
+}
+
+/// Formats the permission bits of the mode either like `ls -l` (`rwxr-xr-x`) or in octal (`755`)
+pub fn format_permissions(mode: u32, format: PermissionsFormat) -> String {
+    match format {
+        PermissionsFormat::Symbolic => {
+            let mut result = String::with_capacity(9);
+
+            for shift in [6, 3, 0] {
+                let bits = (mode >> shift) & 0o7;
+
+                result.push(if bits & 0o4 != 0 { 'r' } else { '-' });
+                result.push(if bits & 0o2 != 0 { 'w' } else { '-' });
+                result.push(if bits & 0o1 != 0 { 'x' } else { '-' });
+            }
+
+            result
+        }
+        PermissionsFormat::Octal => format!("{:03o}", mode & 0o777),
+    }
 }
 
 /// This struct represents the data that will be used to render an entry in the list. It is used in
@@ -72,6 +235,11 @@ pub struct EntryRenderData<'a> {
     search_hit: &'a str,
     suffix: &'a str,
 
+    /// Whether an ellipsis should be rendered before the prefix, because it has been truncated
+    has_leading_ellipsis: bool,
+    /// Whether an ellipsis should be rendered after the suffix, because it has been truncated
+    has_trailing_ellipsis: bool,
+
     /// The character that shouldn't appear in a hotkey sequence for the entry. That's normally the
     /// first character of the name, first character after the search hit or the first character
     /// after the dot in the name (if the name starts with a dot).
@@ -83,12 +251,29 @@ pub struct EntryRenderData<'a> {
     /// The kind of the entry, we need to keep track of this because we render directories
     /// differently than files
     pub kind: &'a EntryKind,
+    /// The `ls -F` style indicator rendered after the name, only set when classifying entries
+    pub type_indicator: Option<char>,
+    /// The style applied to the search hit
+    pub search_hit_style: Style,
+    /// The color of the file (by its extension), when it's not set the default file style is used
+    pub file_color: Option<Color>,
+    /// The matching line of the file's contents, when the entry matched by its contents
+    pub content_snippet: Option<&'a str>,
+    /// The formatted permissions rendered in front of the name, only set when they're shown
+    pub permissions: Option<String>,
+    /// The owner (`user:group`) rendered in front of the name, only set when it's shown
+    pub owner: Option<String>,
+    /// The name of the parent directory rendered (dimmed) after the name, it's only set when
+    /// other shown entries have the same name so that they can be told apart
+    pub parent_hint: Option<String>,
+    /// Whether the `/` after the name of a directory is left out
+    pub hide_trailing_slash: bool,
     /// The key combo sequence assigned to the entry, it's an optional sequence of key combos
     pub key_combo_sequence: Option<Vec<KeyCombo>>,
 }
 
 impl EntryRenderData<'_> {
-    pub fn from_entry<T: AsRef<str>>(entry: &Entry, search_query: T) -> EntryRenderData {
+    pub fn from_entry<T: AsRef<str>>(entry: &Entry, search_query: T) -> EntryRenderData<'_> {
         // Since our "search"/"filter" is case insensitive, and our for entries are always in lower
         // case, we need to make sure that the character we use for `illegal_char_for_hotkey` is
         // lowercase as well
@@ -96,32 +281,55 @@ impl EntryRenderData<'_> {
             name.chars().next().and_then(|c| c.to_lowercase().next())
         }
 
-        if search_query.as_ref().is_empty() {
+        let (search_query, _) = parse_search_query(search_query);
+
+        if search_query.is_empty() {
             return EntryRenderData {
                 prefix: &entry.name,
                 search_hit: "",
                 suffix: "",
+                has_leading_ellipsis: false,
+                has_trailing_ellipsis: false,
                 illegal_char_for_hotkey: get_next_char_lowercase(&entry.name),
                 kind: &entry.kind,
+                type_indicator: None,
+                search_hit_style: Theme::default().search_hit,
+                file_color: None,
+                content_snippet: None,
+                permissions: None,
+                owner: None,
+                parent_hint: None,
+                hide_trailing_slash: false,
                 key_combo_sequence: None,
             };
         }
 
-        let search_query = search_query.as_ref();
-        let name = entry.name.to_lowercase();
-        let search_query = search_query.to_lowercase();
+        let search_hit_range = entry
+            .name_lower
+            .find(&search_query)
+            .and_then(|index| get_range_in_name(&entry.name, index, index + search_query.len()));
 
-        if let Some(index) = name.find(&search_query) {
-            let prefix = &entry.name[..index];
-            let search_hit = &entry.name[index..(index + search_query.len())];
-            let suffix = &entry.name[(index + search_query.len())..];
+        if let Some((start, end)) = search_hit_range {
+            let prefix = &entry.name[..start];
+            let search_hit = &entry.name[start..end];
+            let suffix = &entry.name[end..];
 
             EntryRenderData {
                 prefix,
                 search_hit,
                 suffix,
+                has_leading_ellipsis: false,
+                has_trailing_ellipsis: false,
                 illegal_char_for_hotkey: get_next_char_lowercase(suffix),
                 kind: &entry.kind,
+                type_indicator: None,
+                search_hit_style: Theme::default().search_hit,
+                file_color: None,
+                content_snippet: None,
+                permissions: None,
+                owner: None,
+                parent_hint: None,
+                hide_trailing_slash: false,
                 key_combo_sequence: None,
             }
         } else {
@@ -129,28 +337,164 @@ impl EntryRenderData<'_> {
                 prefix: &entry.name,
                 search_hit: "",
                 suffix: "",
+                has_leading_ellipsis: false,
+                has_trailing_ellipsis: false,
                 illegal_char_for_hotkey: get_next_char_lowercase(&entry.name),
                 kind: &entry.kind,
+                type_indicator: None,
+                search_hit_style: Theme::default().search_hit,
+                file_color: None,
+                content_snippet: None,
+                permissions: None,
+                owner: None,
+                parent_hint: None,
+                hide_trailing_slash: false,
                 key_combo_sequence: None,
             }
         }
     }
+
+    /// Truncates the rendered name so that it fits in the given width (in terminal columns), the
+    /// removed parts are replaced with an ellipsis. The search hit is never truncated, instead we
+    /// trim the suffix first and then the part of the prefix that is furthest from the search hit.
+    pub fn truncate(&mut self, max_width: usize) {
+        let prefix_width = self.prefix.width();
+        let search_hit_width = self.search_hit.width();
+
+        if prefix_width + search_hit_width + self.suffix.width() <= max_width {
+            return;
+        }
+
+        if self.search_hit.is_empty() {
+            // There's nothing to preserve, so we keep the start of the name
+            self.prefix = take_start_within_width(self.prefix, max_width.saturating_sub(1));
+            self.suffix = "";
+            self.has_trailing_ellipsis = true;
+            return;
+        }
+
+        if !self.suffix.is_empty() {
+            let available_width = max_width.saturating_sub(prefix_width + search_hit_width + 1);
+            self.suffix = take_start_within_width(self.suffix, available_width);
+            self.has_trailing_ellipsis = true;
+        }
+
+        let trailing_width = usize::from(self.has_trailing_ellipsis);
+
+        if prefix_width + search_hit_width + self.suffix.width() + trailing_width > max_width {
+            let available_width = max_width.saturating_sub(search_hit_width + trailing_width + 1);
+            self.prefix = take_end_within_width(self.prefix, available_width);
+            self.has_leading_ellipsis = true;
+        }
+    }
+}
+
+/// Maps a byte range of the lowercase name back onto the name. Lowercasing can change the length
+/// of a character (e.g. `Ⱥ` takes 2 bytes, but `ⱥ` takes 3), so the indexes don't always line up.
+/// Returns `None` when the range starts or ends within the lowercase form of a single character.
+fn get_range_in_name(name: &str, start: usize, end: usize) -> Option<(usize, usize)> {
+    let mut name_start = None;
+    let mut lowercase_index = 0;
+
+    for (index, c) in name.char_indices() {
+        if lowercase_index == start {
+            name_start = Some(index);
+        }
+
+        if lowercase_index == end {
+            return name_start.map(|x| (x, index));
+        }
+
+        lowercase_index += c.to_lowercase().map(char::len_utf8).sum::<usize>();
+    }
+
+    name_start
+        .filter(|_| lowercase_index == end)
+        .map(|x| (x, name.len()))
+}
+
+/// Returns the longest start of the value that fits in the given width
+fn take_start_within_width(value: &str, max_width: usize) -> &str {
+    let mut width = 0;
+
+    for (index, c) in value.char_indices() {
+        width += c.width().unwrap_or_default();
+
+        if width > max_width {
+            return &value[..index];
+        }
+    }
+
+    value
+}
+
+/// Returns the longest end of the value that fits in the given width
+fn take_end_within_width(value: &str, max_width: usize) -> &str {
+    let mut width = 0;
+
+    for (index, c) in value.char_indices().rev() {
+        width += c.width().unwrap_or_default();
+
+        if width > max_width {
+            return &value[(index + c.len_utf8())..];
+        }
+    }
+
+    value
 }
 
 impl<'a> From<EntryRenderData<'a>> for ListItem<'a> {
+    fn from(value: EntryRenderData<'a>) -> Self {
+        ListItem::new(Line::from(value))
+    }
+}
+
+impl<'a> From<EntryRenderData<'a>> for Line<'a> {
     fn from(value: EntryRenderData<'a>) -> Self {
         let mut spans: Vec<Span> = Vec::new();
 
+        if let Some(permissions) = value.permissions {
+            spans.push(Span::styled(
+                format!("{permissions} "),
+                Style::default().dark_gray(),
+            ));
+        }
+
+        if let Some(owner) = value.owner {
+            spans.push(Span::styled(
+                format!("{owner} "),
+                Style::default().dark_gray(),
+            ));
+        }
+
+        if value.has_leading_ellipsis {
+            spans.push(Span::raw("…"));
+        }
+
         // we want to display the search hit with underscore
         spans.push(Span::raw(value.prefix));
-        spans.push(Span::styled(
-            value.search_hit,
-            Style::default().underlined(),
-        ));
+        spans.push(Span::styled(value.search_hit, value.search_hit_style));
         spans.push(Span::raw(value.suffix));
 
+        if value.has_trailing_ellipsis {
+            spans.push(Span::raw("…"));
+        }
+
+        if let Some(type_indicator) = value.type_indicator {
+            spans.push(Span::raw(type_indicator.to_string()));
+        }
+
         if value.kind == &EntryKind::Directory {
-            spans.push(Span::raw("/"));
+            if !value.hide_trailing_slash {
+                spans.push(Span::raw("/"));
+            }
+
+            if let Some(parent_hint) = value.parent_hint {
+                spans.push(Span::styled(
+                    format!(" ({parent_hint})"),
+                    Style::default().dark_gray(),
+                ));
+            }
 
             if let Some(key_combo_sequence) = value.key_combo_sequence {
                 spans.push(Span::raw("  ").style(Style::default().dark_gray()));
@@ -162,14 +506,27 @@ impl<'a> From<EntryRenderData<'a>> for ListItem<'a> {
                 }
             }
 
-            let line = Line::from(spans);
-            let style = Style::new().bold().fg(Color::White);
-
-            ListItem::new(line).style(style)
+            Line::from(spans).style(Style::new().bold().fg(Color::White))
         } else {
-            let style = Style::new().dark_gray();
-            let k = Line::from(spans);
-            ListItem::new(k).style(style)
+            if let Some(parent_hint) = value.parent_hint {
+                spans.push(Span::styled(
+                    format!(" ({parent_hint})"),
+                    Style::default().dark_gray(),
+                ));
+            }
+
+            if let Some(content_snippet) = value.content_snippet {
+                spans.push(Span::styled(
+                    format!("  {content_snippet}"),
+                    Style::default().italic(),
+                ));
+            }
+
+            let style = match value.file_color {
+                Some(color) => Style::new().fg(color),
+                None => Style::new().dark_gray(),
+            };
+            Line::from(spans).style(style)
         }
     }
 }
@@ -178,6 +535,17 @@ impl<'a> From<EntryRenderData<'a>> for ListItem<'a> {
 pub struct EntryList {
     pub items: Vec<Entry>,
     pub filtered_indices: Option<Vec<usize>>,
+
+    /// Whether the filtered indices were capped by the results limit, meaning there are more
+    /// matches than the ones in `filtered_indices`
+    pub is_capped: bool,
+
+    /// The snippets of the files that matched the search query by their contents, keyed by the
+    /// path of the file
+    pub content_snippets: HashMap<PathBuf, String>,
+
+    /// The number of entries that were left out of the listing because they couldn't be read
+    pub unreadable_count: usize,
 }
 
 impl EntryList {
@@ -186,6 +554,109 @@ impl EntryList {
         self.items.len()
     }
 
+    /// The number of entries that are currently shown (the ones that match the search query)
+    pub fn filtered_len(&self) -> usize {
+        match &self.filtered_indices {
+            Some(indices) => indices.len(),
+            None => self.items.len(),
+        }
+    }
+
+    /// Whether there are no entries to show, either because the list is empty or because nothing
+    /// matches the search query
+    pub fn is_empty(&self) -> bool {
+        self.filtered_len() == 0
+    }
+
+    /// Returns an index that is valid for the currently shown entries, the selection can point
+    /// beyond them after filtering (or after selecting the last entry, which ratatui only
+    /// resolves when rendering). Returns 0 for an empty list.
+    pub fn clamp_selection(&self, list_state: &ListState) -> usize {
+        list_state
+            .selected()
+            .unwrap_or_default()
+            .min(self.filtered_len().saturating_sub(1))
+    }
+
+    /// Names the entries either by their full path or just by their last component, only the
+    /// displayed (and searched) name changes, the entries still point to their full path.
+    pub fn set_path_names(&mut self, basename_only: bool) {
+        for entry in self.items.iter_mut() {
+            entry.name = get_path_name(&entry.path, basename_only);
+            entry.name_lower = entry.name.to_lowercase();
+        }
+    }
+
+    /// Adds the files that contain the search query to the filtered indices (see
+    /// `content_search`), this should be called after `update_filtered_indices` with the same
+    /// query. At most `content_search::MAX_FILES_PER_QUERY` files are read, regardless of `limit`.
+    pub fn add_content_matches<T: AsRef<str>>(&mut self, value: T, limit: Option<usize>) {
+        let (value, directories_only) = parse_search_query(value);
+
+        // Only files are searched by their contents
+        if directories_only {
+            return;
+        }
+
+        let Some(indices) = &mut self.filtered_indices else {
+            return;
+        };
+
+        let mut files_read = 0;
+
+        for (i, entry) in self.items.iter().enumerate() {
+            // The indices are sorted, since they were collected in order
+            if indices.binary_search(&i).is_ok() || entry.kind == EntryKind::Directory {
+                continue;
+            }
+
+            // This file is a candidate that goes unread, so there may be more matches than shown
+            if limit.is_some_and(|x| indices.len() >= x)
+                || files_read >= content_search::MAX_FILES_PER_QUERY
+            {
+                self.is_capped = true;
+                break;
+            }
+
+            files_read += 1;
+
+            if let Some(snippet) = content_search::find_snippet(&entry.path, &value) {
+                let position = indices.binary_search(&i).unwrap_or_else(|x| x);
+                indices.insert(position, i);
+                self.content_snippets.insert(entry.path.clone(), snippet);
+            }
+        }
+    }
+
+    /// Replaces the matches of the search query with the entries that don't match it, at most
+    /// `limit` of them. Does nothing while there's no query.
+    pub fn invert_filtered_indices(&mut self, limit: Option<usize>) {
+        let Some(indices) = &self.filtered_indices else {
+            return;
+        };
+
+        // The indices are sorted, since they were collected in order
+        let mut inverted: Vec<usize> = (0..self.items.len())
+            .filter(|i| indices.binary_search(i).is_err())
+            .collect();
+
+        self.is_capped = false;
+
+        if let Some(limit) = limit {
+            self.is_capped = inverted.len() > limit;
+            inverted.truncate(limit);
+        }
+
+        // None of the shown entries matched by their contents
+        self.content_snippets.clear();
+        self.filtered_indices = Some(inverted);
+    }
+
+    /// Returns the content snippet of the entry if it matched the search query by its contents
+    pub fn get_content_snippet(&self, entry: &Entry) -> Option<&str> {
+        self.content_snippets.get(&entry.path).map(String::as_str)
+    }
+
     pub fn get_filtered_entries(&self) -> Vec<&Entry> {
         match &self.filtered_indices {
             Some(indices) => indices.iter().map(|&i| &self.items[i]).collect(),
@@ -193,44 +664,164 @@ impl EntryList {
         }
     }
 
-    pub fn update_filtered_indices<T: AsRef<str>>(&mut self, value: T) {
-        let value = value.as_ref().to_lowercase();
+    /// Filters the entries by the given search query, a query prefixed with `d:` only matches
+    /// directories. When a limit is given we stop looking once we've collected that many matches
+    /// and flag the list as capped if there were more.
+    pub fn update_filtered_indices<T: AsRef<str>>(&mut self, value: T, limit: Option<usize>) {
+        let (value, directories_only) = parse_search_query(value);
+        self.is_capped = false;
+        self.content_snippets.clear();
 
-        if value.is_empty() {
+        if value.is_empty() && !directories_only {
             self.filtered_indices = None;
         } else {
-            let indices = self
+            // We collect one extra match so that we know whether the results were capped
+            let mut indices: Vec<usize> = self
                 .items
                 .iter()
                 .enumerate()
                 .filter_map(|(i, entry)| {
-                    if entry.name.to_lowercase().contains(&value) {
+                    let is_kind_allowed = !directories_only || entry.kind == EntryKind::Directory;
+
+                    if is_kind_allowed && entry.name_lower.contains(&value) {
                         Some(i)
                     } else {
                         None
                     }
                 })
+                .take(limit.map_or(usize::MAX, |x| x.saturating_add(1)))
                 .collect();
 
+            if let Some(limit) = limit {
+                self.is_capped = indices.len() > limit;
+                indices.truncate(limit);
+            }
+
             self.filtered_indices = Some(indices);
         }
     }
 }
 
+/// Builds a list out of arbitrary paths (for example the most accessed paths), the entries are
+/// named by their full path.
+impl From<Vec<PathBuf>> for EntryList {
+    fn from(value: Vec<PathBuf>) -> Self {
+        let items = value
+            .into_iter()
+            .map(|path| {
+                let kind = if path.is_dir() {
+                    EntryKind::Directory
+                } else {
+                    let extension = get_extension(&path);
+                    EntryKind::File { extension }
+                };
+
+                let name = get_path_name(&path, false);
+
+                Entry {
+                    name_lower: name.to_lowercase(),
+                    name,
+                    path,
+                    kind,
+                    is_symlink: false,
+                    is_executable: false,
+                    permissions: None,
+                    owner_ids: None,
+                }
+            })
+            .collect();
+
+        EntryList {
+            items,
+            ..Default::default()
+        }
+    }
+}
+
+/// Reads newline separated paths (e.g. piped to the app) to be listed with `EntryList::from`, the
+/// empty lines are skipped.
+pub fn read_paths<R: BufRead>(reader: R) -> io::Result<Vec<PathBuf>> {
+    let mut paths = Vec::new();
+
+    for line in reader.lines() {
+        let line = line?;
+        let line = line.trim_end_matches('\r');
+
+        if !line.is_empty() {
+            paths.push(PathBuf::from(line));
+        }
+    }
+
+    Ok(paths)
+}
+
+/// The extensions that are made out of two parts, they're treated as a unit (`archive.tar.gz` has
+/// the `tar.gz` extension rather than `gz`).
+const COMPOUND_EXTENSIONS: [&str; 7] = [
+    "tar.gz", "tar.bz2", "tar.xz", "tar.zst", "tar.lz", "tar.lz4", "tar.br",
+];
+
+/// Returns the extension of the file at the given path, compound extensions (see
+/// `COMPOUND_EXTENSIONS`) are returned as a whole. Hidden files like `.gitignore` have no
+/// extension.
+pub fn get_extension<T: AsRef<Path>>(path: T) -> Option<String> {
+    let path = path.as_ref();
+    let extension = path.extension()?.to_string_lossy().into_owned();
+
+    let compound = path
+        .file_stem()
+        .map(Path::new)
+        .and_then(Path::extension)
+        .map(|x| format!("{}.{extension}", x.to_string_lossy()))
+        .filter(|x| {
+            COMPOUND_EXTENSIONS
+                .iter()
+                .any(|compound| x.eq_ignore_ascii_case(compound))
+        });
+
+    Some(compound.unwrap_or(extension))
+}
+
+/// Returns the name of the path used for display and search, either its full path or just its
+/// last component.
+fn get_path_name(path: &Path, basename_only: bool) -> String {
+    let name = match path.file_name() {
+        Some(file_name) if basename_only => file_name.to_string_lossy(),
+        _ => path.to_string_lossy(),
+    };
+
+    name.nfc().collect()
+}
+
 impl TryFrom<ReadDir> for EntryList {
     type Error = anyhow::Error;
 
     fn try_from(value: ReadDir) -> Result<Self, Self::Error> {
+        EntryList::from_read_dir(value, true)
+    }
+}
+
+impl EntryList {
+    /// Lists the directory, see `Entry::from_dir_entry` for `read_metadata`. The entries that
+    /// can't be read (e.g. removed while listing) are skipped and counted in `unreadable_count`.
+    pub fn from_read_dir(value: ReadDir, read_metadata: bool) -> anyhow::Result<Self> {
         let mut items = Vec::new();
+        let mut unreadable_count = 0;
 
         for dir_entry_result in value.into_iter() {
-            let dir_entry = dir_entry_result?;
-            let item = Entry::try_from(dir_entry)?;
-            items.push(item);
+            let item = dir_entry_result
+                .map_err(anyhow::Error::from)
+                .and_then(|x| Entry::from_dir_entry(x, read_metadata));
+
+            match item {
+                Ok(item) => items.push(item),
+                Err(_) => unreadable_count += 1,
+            }
         }
 
         Ok(EntryList {
             items,
+            unreadable_count,
             ..Default::default()
         })
     }
@@ -240,6 +831,407 @@ impl TryFrom<ReadDir> for EntryList {
 mod tests {
     use super::*;
 
+    #[test]
+    fn get_extension_treats_compound_extensions_as_a_unit() {
+        assert_eq!(get_extension("/tmp/archive.tar.gz"), Some("tar.gz".into()));
+        assert_eq!(get_extension("/tmp/backup.TAR.BZ2"), Some("TAR.BZ2".into()));
+        assert_eq!(get_extension("/tmp/notes.old.gz"), Some("gz".into()));
+        assert_eq!(get_extension("/tmp/main.rs"), Some("rs".into()));
+        assert_eq!(get_extension("/tmp/.gitignore"), None);
+        assert_eq!(get_extension("/tmp/.tar.gz"), Some("gz".into()));
+        assert_eq!(get_extension("/tmp/Makefile"), None);
+    }
+
+    mod entry {
+        use super::*;
+
+        #[cfg(unix)]
+        #[test]
+        fn entry_reads_permissions_from_metadata() {
+            use std::os::unix::fs::PermissionsExt;
+
+            let temp_dir = tempfile::tempdir().unwrap();
+            let path = temp_dir.path().join("script.sh");
+            std::fs::write(&path, "").unwrap();
+            std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o754)).unwrap();
+
+            let dir_entry = std::fs::read_dir(temp_dir.path())
+                .unwrap()
+                .next()
+                .unwrap()
+                .unwrap();
+            let entry = Entry::try_from(dir_entry).unwrap();
+            let permissions = entry.permissions.unwrap();
+
+            assert!(entry.is_executable);
+            assert_eq!(
+                format_permissions(permissions, PermissionsFormat::Symbolic),
+                "rwxr-xr--"
+            );
+            assert_eq!(
+                format_permissions(permissions, PermissionsFormat::Octal),
+                "754"
+            );
+        }
+
+        #[test]
+        fn format_permissions_works_correctly() {
+            assert_eq!(
+                format_permissions(0o100644, PermissionsFormat::Symbolic),
+                "rw-r--r--"
+            );
+            assert_eq!(format_permissions(0o40700, PermissionsFormat::Octal), "700");
+            assert_eq!(format_permissions(0o7, PermissionsFormat::Octal), "007");
+        }
+
+        #[test]
+        fn path_relative_to_works_correctly_for_nested_entry() {
+            let entry = Entry {
+                name: "main.rs".into(),
+                name_lower: "main.rs".into(),
+                kind: EntryKind::File {
+                    extension: Some("rs".into()),
+                },
+                path: PathBuf::from("/home/user/project/src/main.rs"),
+                is_symlink: false,
+                is_executable: false,
+                permissions: None,
+                owner_ids: None,
+            };
+
+            assert_eq!(
+                entry.path_relative_to("/home/user/project"),
+                Path::new("src/main.rs")
+            );
+            assert_eq!(
+                entry.path_relative_to("/home/user"),
+                Path::new("project/src/main.rs")
+            );
+        }
+
+        #[test]
+        fn path_relative_to_falls_back_to_absolute_path() {
+            let entry = Entry {
+                name: "main.rs".into(),
+                name_lower: "main.rs".into(),
+                kind: EntryKind::File {
+                    extension: Some("rs".into()),
+                },
+                path: PathBuf::from("/home/user/project/src/main.rs"),
+                is_symlink: false,
+                is_executable: false,
+                permissions: None,
+                owner_ids: None,
+            };
+
+            assert_eq!(
+                entry.path_relative_to("/tmp"),
+                Path::new("/home/user/project/src/main.rs")
+            );
+        }
+    }
+
+    mod entry_list {
+        use super::*;
+
+        #[test]
+        fn clamp_selection_keeps_the_selection_within_the_filtered_entries() {
+            let mut entry_list = EntryList::from(vec![
+                PathBuf::from("alpha"),
+                PathBuf::from("beta"),
+                PathBuf::from("gamma"),
+                PathBuf::from("delta"),
+            ]);
+            let mut list_state = ListState::default();
+
+            assert!(!entry_list.is_empty());
+            assert_eq!(entry_list.clamp_selection(&list_state), 0);
+
+            list_state.select(Some(3));
+            assert_eq!(entry_list.clamp_selection(&list_state), 3);
+
+            // Only "beta" and "delta" match, so the selection falls back to the last of them
+            entry_list.update_filtered_indices("ta", None);
+            assert_eq!(entry_list.filtered_len(), 2);
+            assert_eq!(entry_list.clamp_selection(&list_state), 1);
+
+            list_state.select_last();
+            assert_eq!(entry_list.clamp_selection(&list_state), 1);
+
+            entry_list.update_filtered_indices("nothing", None);
+            assert!(entry_list.is_empty());
+            assert_eq!(entry_list.clamp_selection(&list_state), 0);
+        }
+
+        #[test]
+        fn entry_list_from_paths_can_switch_between_full_paths_and_basenames() {
+            let mut entry_list = EntryList::from(vec![
+                PathBuf::from("/home/user/projects/tiny-fe"),
+                PathBuf::from("/home/user/Documents"),
+            ]);
+
+            let names: Vec<&str> = entry_list.items.iter().map(|x| x.name.as_str()).collect();
+            assert_eq!(
+                names,
+                vec!["/home/user/projects/tiny-fe", "/home/user/Documents"]
+            );
+
+            entry_list.set_path_names(true);
+
+            let names: Vec<&str> = entry_list.items.iter().map(|x| x.name.as_str()).collect();
+            assert_eq!(names, vec!["tiny-fe", "Documents"]);
+            assert_eq!(
+                entry_list.items[0].path,
+                PathBuf::from("/home/user/projects/tiny-fe")
+            );
+
+            entry_list.set_path_names(false);
+
+            let names: Vec<&str> = entry_list.items.iter().map(|x| x.name.as_str()).collect();
+            assert_eq!(
+                names,
+                vec!["/home/user/projects/tiny-fe", "/home/user/Documents"]
+            );
+        }
+
+        fn create_entry_list(names: &[&str]) -> EntryList {
+            EntryList {
+                items: names
+                    .iter()
+                    .map(|name| Entry {
+                        path: PathBuf::from("/home/user").join(name),
+                        kind: EntryKind::Directory,
+                        name: name.to_string(),
+                        name_lower: name.to_lowercase(),
+                        is_symlink: false,
+                        is_executable: false,
+                        permissions: None,
+                        owner_ids: None,
+                    })
+                    .collect(),
+                ..Default::default()
+            }
+        }
+
+        #[test]
+        fn entry_list_search_is_case_insensitive() {
+            let mut entry_list = create_entry_list(&["Documents", "downloads", "DESKTOP", "music"]);
+
+            entry_list.update_filtered_indices("D", None);
+            assert_eq!(entry_list.filtered_indices, Some(vec![0, 1, 2]));
+
+            entry_list.update_filtered_indices("desk", None);
+            assert_eq!(entry_list.filtered_indices, Some(vec![2]));
+
+            entry_list.update_filtered_indices("MUSIC", None);
+            assert_eq!(entry_list.filtered_indices, Some(vec![3]));
+        }
+
+        #[test]
+        fn entry_list_lowercases_the_names_once_across_searches() {
+            let names = (0..100_000).map(|i| format!("Dir{i}")).collect::<Vec<_>>();
+            let mut entry_list =
+                create_entry_list(&names.iter().map(String::as_str).collect::<Vec<_>>());
+
+            // The names are lowercased once, when the entries are created, and the keystrokes only
+            // read them. A search that lowercased the names again wouldn't match this one.
+            entry_list.items[99_999].name_lower = "cached".into();
+
+            for query in ["c", "ca", "cached"] {
+                entry_list.update_filtered_indices(query, None);
+                assert_eq!(entry_list.filtered_indices, Some(vec![99_999]));
+            }
+
+            entry_list.update_filtered_indices("dir99999", None);
+            assert_eq!(entry_list.filtered_indices, Some(vec![]));
+        }
+
+        #[test]
+        fn entry_list_search_follows_renamed_entries() {
+            let mut entry_list = EntryList::from(vec![
+                PathBuf::from("/home/user/Apple"),
+                PathBuf::from("/tmp/banana"),
+            ]);
+
+            entry_list.update_filtered_indices("user", None);
+            assert_eq!(entry_list.filtered_indices, Some(vec![0]));
+
+            entry_list.set_path_names(true);
+            entry_list.update_filtered_indices("user", None);
+            assert_eq!(entry_list.filtered_indices, Some(vec![]));
+
+            entry_list.update_filtered_indices("apple", None);
+            assert_eq!(entry_list.filtered_indices, Some(vec![0]));
+        }
+
+        #[test]
+        fn entry_list_filtered_indices_are_capped_by_limit() {
+            let mut entry_list = create_entry_list(&["dir1", "dir2", "dir3", "dir4", "other"]);
+
+            entry_list.update_filtered_indices("dir", Some(2));
+
+            assert_eq!(entry_list.filtered_indices, Some(vec![0, 1]));
+            assert!(entry_list.is_capped);
+            assert_eq!(entry_list.get_filtered_entries().len(), 2);
+        }
+
+        #[test]
+        fn entry_list_content_matches_read_a_limited_number_of_files() {
+            let temp_dir = tempfile::tempdir().unwrap();
+            let create_entry_list = |count: usize| {
+                for i in 0..count {
+                    let path = temp_dir.path().join(format!("file{i:03}.txt"));
+                    std::fs::write(path, "a needle in a haystack\n").unwrap();
+                }
+
+                EntryList::from_read_dir(std::fs::read_dir(temp_dir.path()).unwrap(), false)
+                    .unwrap()
+            };
+
+            let mut entry_list = create_entry_list(content_search::MAX_FILES_PER_QUERY);
+            entry_list.update_filtered_indices("needle", None);
+            entry_list.add_content_matches("needle", None);
+
+            assert_eq!(
+                entry_list.filtered_len(),
+                content_search::MAX_FILES_PER_QUERY
+            );
+            assert!(!entry_list.is_capped);
+
+            // The file left over isn't read, even without a results limit
+            let mut entry_list = create_entry_list(content_search::MAX_FILES_PER_QUERY + 1);
+            entry_list.update_filtered_indices("needle", None);
+            entry_list.add_content_matches("needle", None);
+
+            assert_eq!(
+                entry_list.filtered_len(),
+                content_search::MAX_FILES_PER_QUERY
+            );
+            assert!(entry_list.is_capped);
+        }
+
+        #[test]
+        fn entry_list_content_matches_are_capped_only_when_a_file_is_skipped() {
+            let temp_dir = tempfile::tempdir().unwrap();
+            std::fs::write(temp_dir.path().join("one.txt"), "needle\n").unwrap();
+            std::fs::write(temp_dir.path().join("two.txt"), "needle\n").unwrap();
+
+            let mut entry_list =
+                EntryList::from_read_dir(std::fs::read_dir(temp_dir.path()).unwrap(), false)
+                    .unwrap();
+
+            // The limit is reached with the last file, nothing is left unread
+            entry_list.update_filtered_indices("needle", Some(2));
+            entry_list.add_content_matches("needle", Some(2));
+            assert_eq!(entry_list.filtered_len(), 2);
+            assert!(!entry_list.is_capped);
+
+            entry_list.update_filtered_indices("needle", Some(1));
+            entry_list.add_content_matches("needle", Some(1));
+            assert_eq!(entry_list.filtered_len(), 1);
+            assert!(entry_list.is_capped);
+        }
+
+        #[test]
+        fn entry_list_filtered_indices_are_not_capped_within_limit() {
+            let mut entry_list = create_entry_list(&["dir1", "dir2", "dir3", "dir4", "other"]);
+
+            entry_list.update_filtered_indices("dir", Some(4));
+            assert_eq!(entry_list.filtered_indices, Some(vec![0, 1, 2, 3]));
+            assert!(!entry_list.is_capped);
+
+            entry_list.update_filtered_indices("dir", None);
+            assert_eq!(entry_list.filtered_indices, Some(vec![0, 1, 2, 3]));
+            assert!(!entry_list.is_capped);
+        }
+
+        #[cfg(unix)]
+        #[test]
+        fn entry_list_from_read_dir_skips_unreadable_entries() {
+            let temp_dir = tempfile::tempdir().unwrap();
+            for name in ["alpha", "beta", "gamma"] {
+                std::fs::write(temp_dir.path().join(name), "").unwrap();
+            }
+
+            // Reading the first entry fetches the whole (small) directory, the files removed
+            // afterwards are still listed but their metadata can't be read anymore
+            let mut entries = std::fs::read_dir(temp_dir.path()).unwrap();
+            let first = entries.next().unwrap().unwrap();
+            for name in ["alpha", "beta", "gamma"] {
+                if first.file_name() != name {
+                    std::fs::remove_file(temp_dir.path().join(name)).unwrap();
+                }
+            }
+
+            let entry_list = EntryList::from_read_dir(entries, true).unwrap();
+
+            assert_eq!(entry_list.len(), 0);
+            assert_eq!(entry_list.unreadable_count, 2);
+        }
+
+        #[test]
+        fn entry_list_directories_only_prefix_matches_only_directories() {
+            let mut entry_list = create_entry_list(&["sub", "subtitles.srt", "other", "Subway"]);
+            entry_list.items[1].kind = EntryKind::File {
+                extension: Some("srt".into()),
+            };
+
+            entry_list.update_filtered_indices("sub", None);
+            assert_eq!(entry_list.filtered_indices, Some(vec![0, 1, 3]));
+
+            entry_list.update_filtered_indices("d:sub", None);
+            assert_eq!(entry_list.filtered_indices, Some(vec![0, 3]));
+
+            // The prefix alone lists all the directories
+            entry_list.update_filtered_indices("D:", None);
+            assert_eq!(entry_list.filtered_indices, Some(vec![0, 2, 3]));
+        }
+
+        #[test]
+        fn entry_list_inverted_filtered_indices_are_the_complement_of_the_matches() {
+            let mut entry_list = create_entry_list(&["dir1", "log1", "dir2", "log2", "other"]);
+
+            entry_list.update_filtered_indices("log", None);
+            assert_eq!(entry_list.filtered_indices, Some(vec![1, 3]));
+
+            entry_list.invert_filtered_indices(None);
+            assert_eq!(entry_list.filtered_indices, Some(vec![0, 2, 4]));
+            assert!(!entry_list.is_capped);
+
+            entry_list.update_filtered_indices("log", None);
+            entry_list.invert_filtered_indices(Some(2));
+            assert_eq!(entry_list.filtered_indices, Some(vec![0, 2]));
+            assert!(entry_list.is_capped);
+
+            // Without a query everything is shown either way
+            entry_list.update_filtered_indices("", None);
+            entry_list.invert_filtered_indices(None);
+            assert_eq!(entry_list.filtered_indices, None);
+        }
+
+        #[test]
+        fn entry_list_matches_nfd_names_with_nfc_query() {
+            let temp_dir = tempfile::tempdir().unwrap();
+
+            // "café" with a combining acute accent (NFD), the way macOS stores it
+            std::fs::create_dir(temp_dir.path().join("cafe\u{301}")).unwrap();
+            std::fs::create_dir(temp_dir.path().join("tea")).unwrap();
+
+            let mut entry_list =
+                EntryList::try_from(std::fs::read_dir(temp_dir.path()).unwrap()).unwrap();
+
+            // "café" with a precomposed é (NFC), the way it's normally typed
+            entry_list.update_filtered_indices("caf\u{e9}", None);
+
+            let entries = entry_list.get_filtered_entries();
+            assert_eq!(entries.len(), 1);
+            assert_eq!(entries[0].path, temp_dir.path().join("cafe\u{301}"));
+
+            let entry_render_data = EntryRenderData::from_entry(entries[0], "caf\u{e9}");
+            assert_eq!(entry_render_data.search_hit, "caf\u{e9}");
+        }
+    }
+
     mod entry_render_data {
         use super::*;
 
@@ -247,10 +1239,15 @@ mod tests {
         fn entry_render_data_from_entry_works_correctly_with_search_query() {
             let entry = Entry {
                 name: "Cargo.toml".into(),
+                name_lower: "cargo.toml".into(),
                 kind: EntryKind::File {
                     extension: Some("toml".into()),
                 },
                 path: PathBuf::from("/home/user/Cargo.toml"),
+                is_symlink: false,
+                is_executable: false,
+                permissions: None,
+                owner_ids: None,
             };
 
             let entry_render_data: EntryRenderData = EntryRenderData::from_entry(&entry, "car");
@@ -261,10 +1258,20 @@ mod tests {
                     prefix: "",
                     search_hit: "Car",
                     suffix: "go.toml",
+                    has_leading_ellipsis: false,
+                    has_trailing_ellipsis: false,
                     illegal_char_for_hotkey: Some('g'),
                     kind: &EntryKind::File {
                         extension: Some("toml".into())
                     },
+                    type_indicator: None,
+                    search_hit_style: Style::default().underlined(),
+                    file_color: None,
+                    content_snippet: None,
+                    permissions: None,
+                    owner: None,
+                    parent_hint: None,
+                    hide_trailing_slash: false,
                     key_combo_sequence: None,
                 }
             );
@@ -277,10 +1284,20 @@ mod tests {
                     prefix: "Cargo.",
                     search_hit: "toml",
                     suffix: "",
+                    has_leading_ellipsis: false,
+                    has_trailing_ellipsis: false,
                     illegal_char_for_hotkey: None,
                     kind: &EntryKind::File {
                         extension: Some("toml".into())
                     },
+                    type_indicator: None,
+                    search_hit_style: Style::default().underlined(),
+                    file_color: None,
+                    content_snippet: None,
+                    permissions: None,
+                    owner: None,
+                    parent_hint: None,
+                    hide_trailing_slash: false,
                     key_combo_sequence: None,
                 }
             );
@@ -293,10 +1310,20 @@ mod tests {
                     prefix: "C",
                     search_hit: "argo",
                     suffix: ".toml",
+                    has_leading_ellipsis: false,
+                    has_trailing_ellipsis: false,
                     illegal_char_for_hotkey: Some('.'),
                     kind: &EntryKind::File {
                         extension: Some("toml".into())
                     },
+                    type_indicator: None,
+                    search_hit_style: Style::default().underlined(),
+                    file_color: None,
+                    content_snippet: None,
+                    permissions: None,
+                    owner: None,
+                    parent_hint: None,
+                    hide_trailing_slash: false,
                     key_combo_sequence: None,
                 }
             );
@@ -309,13 +1336,132 @@ mod tests {
                     prefix: "Cargo.toml",
                     search_hit: "",
                     suffix: "",
+                    has_leading_ellipsis: false,
+                    has_trailing_ellipsis: false,
                     illegal_char_for_hotkey: Some('c'),
                     kind: &EntryKind::File {
                         extension: Some("toml".into())
                     },
+                    type_indicator: None,
+                    search_hit_style: Style::default().underlined(),
+                    file_color: None,
+                    content_snippet: None,
+                    permissions: None,
+                    owner: None,
+                    parent_hint: None,
+                    hide_trailing_slash: false,
                     key_combo_sequence: None,
                 }
             );
         }
+
+        #[test]
+        fn entry_render_data_truncate_keeps_start_of_name_without_search_query() {
+            let entry = Entry {
+                name: "a_very_long_directory_name".into(),
+                name_lower: "a_very_long_directory_name".into(),
+                kind: EntryKind::Directory,
+                path: PathBuf::from("/home/user/a_very_long_directory_name"),
+                is_symlink: false,
+                is_executable: false,
+                permissions: None,
+                owner_ids: None,
+            };
+
+            let mut entry_render_data = EntryRenderData::from_entry(&entry, "");
+            entry_render_data.truncate(10);
+
+            assert_eq!(entry_render_data.prefix, "a_very_lo");
+            assert!(!entry_render_data.has_leading_ellipsis);
+            assert!(entry_render_data.has_trailing_ellipsis);
+
+            // Names that already fit are left untouched
+            let mut entry_render_data = EntryRenderData::from_entry(&entry, "");
+            entry_render_data.truncate(100);
+
+            assert_eq!(entry_render_data.prefix, "a_very_long_directory_name");
+            assert!(!entry_render_data.has_trailing_ellipsis);
+        }
+
+        #[test]
+        fn entry_render_data_truncate_never_hides_search_hit() {
+            let entry = Entry {
+                name: "a_very_long_directory_name".into(),
+                name_lower: "a_very_long_directory_name".into(),
+                kind: EntryKind::Directory,
+                path: PathBuf::from("/home/user/a_very_long_directory_name"),
+                is_symlink: false,
+                is_executable: false,
+                permissions: None,
+                owner_ids: None,
+            };
+
+            let mut entry_render_data = EntryRenderData::from_entry(&entry, "directory");
+            entry_render_data.truncate(14);
+
+            assert_eq!(entry_render_data.prefix, "ng_");
+            assert_eq!(entry_render_data.search_hit, "directory");
+            assert_eq!(entry_render_data.suffix, "");
+            assert!(entry_render_data.has_leading_ellipsis);
+            assert!(entry_render_data.has_trailing_ellipsis);
+
+            // The search hit is kept even if it doesn't fit on its own
+            let mut entry_render_data = EntryRenderData::from_entry(&entry, "directory");
+            entry_render_data.truncate(4);
+
+            assert_eq!(entry_render_data.prefix, "");
+            assert_eq!(entry_render_data.search_hit, "directory");
+        }
+
+        #[test]
+        fn entry_render_data_from_entry_handles_names_that_change_length_when_lowercased() {
+            let create_entry = |name: &str| Entry {
+                name: name.into(),
+                name_lower: name.to_lowercase(),
+                kind: EntryKind::Directory,
+                path: PathBuf::from("/home/user").join(name),
+                is_symlink: false,
+                is_executable: false,
+                permissions: None,
+                owner_ids: None,
+            };
+
+            // "ȺB" takes 3 bytes, but "ⱥb" takes 4
+            let entry = create_entry("ȺB");
+            let entry_render_data = EntryRenderData::from_entry(&entry, "b");
+
+            assert_eq!(entry_render_data.prefix, "Ⱥ");
+            assert_eq!(entry_render_data.search_hit, "B");
+            assert_eq!(entry_render_data.suffix, "");
+
+            // "İ" is lowercased to "i" followed by a combining dot, matching only a part of it
+            // isn't highlighted
+            let entry = create_entry("İx");
+            let entry_render_data = EntryRenderData::from_entry(&entry, "i");
+
+            assert_eq!(entry_render_data.prefix, "İx");
+            assert_eq!(entry_render_data.search_hit, "");
+        }
+
+        #[test]
+        fn entry_render_data_truncate_is_width_aware() {
+            let entry = Entry {
+                name: "日本語のディレクトリ".into(),
+                name_lower: "日本語のディレクトリ".into(),
+                kind: EntryKind::Directory,
+                path: PathBuf::from("/home/user/日本語のディレクトリ"),
+                is_symlink: false,
+                is_executable: false,
+                permissions: None,
+                owner_ids: None,
+            };
+
+            let mut entry_render_data = EntryRenderData::from_entry(&entry, "");
+            entry_render_data.truncate(8);
+
+            // Each character takes 2 columns, so only 3 of them fit next to the ellipsis
+            assert_eq!(entry_render_data.prefix, "日本語");
+            assert!(entry_render_data.has_trailing_ellipsis);
+        }
     }
 }
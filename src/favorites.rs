@@ -0,0 +1,96 @@
+//! Numbered quick-access slots for directories, like the bookmarks 1-9 of a browser.
+//!
+//! `Ctrl + s` followed by a number saves the current directory to that slot and `'` followed by
+//! the number goes back to it. The slots are persisted in a plain text file with one line per
+//! slot, an empty line being an unset slot.
+
+use std::path::{Path, PathBuf};
+
+use anyhow::Context;
+
+use crate::path_lines::{read_path_slots, write_path_slots};
+
+/// The number of slots, they're numbered from 1
+pub const SLOTS_COUNT: usize = 9;
+
+#[derive(Debug, Default)]
+pub struct Favorites {
+    /// The file the slots are persisted to, the slots aren't persisted without it
+    file_path: Option<PathBuf>,
+
+    /// The directories saved to the slots, the first one being slot 1
+    slots: [Option<PathBuf>; SLOTS_COUNT],
+}
+
+impl Favorites {
+    /// Loads the slots from the given file, a missing file is treated as all slots being unset.
+    pub fn load_from_disk<T: AsRef<Path>>(file_path: T) -> anyhow::Result<Self> {
+        let file_path = file_path.as_ref().to_path_buf();
+        let mut slots: [Option<PathBuf>; SLOTS_COUNT] = Default::default();
+
+        for (slot, path) in slots.iter_mut().zip(read_path_slots(&file_path)?) {
+            *slot = path;
+        }
+
+        Ok(Favorites {
+            file_path: Some(file_path),
+            slots,
+        })
+    }
+
+    pub fn save_to_disk(&self) -> anyhow::Result<()> {
+        let Some(file_path) = &self.file_path else {
+            return Ok(());
+        };
+
+        write_path_slots(file_path, &self.slots)
+    }
+
+    /// Returns the directory saved to the given slot (numbered from 1)
+    pub fn get(&self, slot: usize) -> Option<&Path> {
+        self.slots.get(slot.checked_sub(1)?)?.as_deref()
+    }
+
+    /// Saves the directory to the given slot (numbered from 1), replacing whatever was there, and
+    /// persists the slots.
+    pub fn set<T: AsRef<Path>>(&mut self, slot: usize, path: T) -> anyhow::Result<()> {
+        let index = slot
+            .checked_sub(1)
+            .filter(|&x| x < SLOTS_COUNT)
+            .with_context(|| format!("There's no slot {slot}"))?;
+
+        self.slots[index] = Some(path.as_ref().to_path_buf());
+        self.save_to_disk()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn favorites_are_persisted_between_loads() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let file_path = temp_dir.path().join("favorites");
+
+        let mut favorites = Favorites::load_from_disk(&file_path).unwrap();
+        favorites.set(1, "/home/user").unwrap();
+        favorites.set(3, "/tmp").unwrap();
+
+        let favorites = Favorites::load_from_disk(&file_path).unwrap();
+        assert_eq!(favorites.get(1), Some(Path::new("/home/user")));
+        assert_eq!(favorites.get(2), None);
+        assert_eq!(favorites.get(3), Some(Path::new("/tmp")));
+    }
+
+    #[test]
+    fn favorites_only_have_slots_one_to_nine() {
+        let mut favorites = Favorites::default();
+
+        assert!(favorites.set(0, "/tmp").is_err());
+        assert!(favorites.set(10, "/tmp").is_err());
+        assert!(favorites.set(9, "/tmp").is_ok());
+        assert_eq!(favorites.get(0), None);
+        assert_eq!(favorites.get(9), Some(Path::new("/tmp")));
+    }
+}
@@ -0,0 +1,60 @@
+use std::{
+    fmt,
+    path::Path,
+    process::{Command, Stdio},
+    thread,
+};
+
+use anyhow::Context;
+
+/// An abstraction over spawning the external hook commands, so that the app can be tested without
+/// running real processes.
+pub trait CommandSpawner: fmt::Debug {
+    /// Spawns the given command line with the path appended as the last argument, without waiting
+    /// for it to finish.
+    fn spawn(&mut self, command: &str, path: &Path) -> anyhow::Result<()>;
+}
+
+/// Spawns the hook commands as real processes. The command line is split on whitespace, the first
+/// word is the program and the rest are its arguments (no shell is involved).
+#[derive(Debug, Default)]
+pub struct SystemCommandSpawner;
+
+impl CommandSpawner for SystemCommandSpawner {
+    fn spawn(&mut self, command: &str, path: &Path) -> anyhow::Result<()> {
+        let mut words = command.split_whitespace();
+        let program = words.next().context("The command is empty")?;
+
+        // The hook must not write over the UI, so we silence all of its output
+        let mut child = Command::new(program)
+            .args(words)
+            .arg(path)
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn()
+            .with_context(|| format!("Failed to run '{program}'"))?;
+
+        // Reap the child in the background so that we don't block the UI or leave zombies behind
+        thread::spawn(move || child.wait());
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn system_command_spawner_fails_for_empty_command() {
+        assert!(SystemCommandSpawner.spawn("  ", Path::new("/")).is_err());
+    }
+
+    #[test]
+    fn system_command_spawner_fails_for_missing_program() {
+        assert!(SystemCommandSpawner
+            .spawn("tiny-fe-missing-program --flag", Path::new("/"))
+            .is_err());
+    }
+}
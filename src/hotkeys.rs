@@ -1,5 +1,6 @@
 use std::{
     collections::{HashMap, HashSet},
+    fmt,
     hash::Hash,
 };
 
@@ -8,6 +9,7 @@ use crossterm::event::{KeyCode, KeyModifiers};
 use crate::{
     app::{Action, InputMode, ListMode},
     entry::{EntryKind, EntryRenderData},
+    favorites::SLOTS_COUNT,
 };
 
 #[derive(Debug, Hash, PartialEq, Eq, Clone, Copy)]
@@ -52,6 +54,35 @@ impl From<(KeyCode, KeyModifiers)> for KeyCombo {
     }
 }
 
+impl fmt::Display for KeyCombo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.modifiers.contains(KeyModifiers::CONTROL) {
+            write!(f, "Ctrl + ")?;
+        }
+
+        if self.modifiers.contains(KeyModifiers::ALT) {
+            write!(f, "Alt + ")?;
+        }
+
+        // Shift is already part of the (uppercase) character
+        match self.key_code {
+            KeyCode::Char(' ') => write!(f, "Space"),
+            KeyCode::Char(c) => write!(f, "{c}"),
+            KeyCode::Backspace => write!(f, "Backspace"),
+            KeyCode::Enter => write!(f, "Enter"),
+            KeyCode::Left => write!(f, "Left"),
+            KeyCode::Right => write!(f, "Right"),
+            KeyCode::Up => write!(f, "Up"),
+            KeyCode::Down => write!(f, "Down"),
+            KeyCode::Home => write!(f, "Home"),
+            KeyCode::End => write!(f, "End"),
+            KeyCode::Tab => write!(f, "Tab"),
+            KeyCode::Esc => write!(f, "Esc"),
+            key_code => write!(f, "{key_code:?}"),
+        }
+    }
+}
+
 #[derive(Debug)]
 pub struct HotkeysTrieNode<T> {
     pub children: HashMap<KeyCombo, HotkeysTrieNode<T>>,
@@ -117,6 +148,31 @@ impl<T> HotkeysTrie<T> {
         self.root.children.clear();
         self.root.value = None;
     }
+
+    /// Returns all the key sequences that lead to a value, together with the value (in no
+    /// particular order)
+    pub fn get_sequences(&self) -> Vec<(Vec<KeyCombo>, &T)> {
+        fn collect<'a, T>(
+            node: &'a HotkeysTrieNode<T>,
+            sequence: &mut Vec<KeyCombo>,
+            result: &mut Vec<(Vec<KeyCombo>, &'a T)>,
+        ) {
+            if let Some(value) = &node.value {
+                result.push((sequence.clone(), value));
+            }
+
+            for (&key_combo, child) in node.children.iter() {
+                sequence.push(key_combo);
+                collect(child, sequence, result);
+                sequence.pop();
+            }
+        }
+
+        let mut result = Vec::new();
+        collect(&self.root, &mut Vec::new(), &mut result);
+
+        result
+    }
 }
 
 impl<T> Default for HotkeysTrie<T> {
@@ -184,6 +240,14 @@ where
             .or_else(|| self.entry_hotkeys.get_value(key_combos))
     }
 
+    /// Returns all the system hotkeys of the given context, with the values they're bound to
+    pub fn get_system_hotkeys(&self, context: &C) -> Vec<(Vec<KeyCombo>, &T)> {
+        self.system_hotkeys
+            .get(context)
+            .map(|trie| trie.get_sequences())
+            .unwrap_or_default()
+    }
+
     pub fn get_hotkey_node(
         &self,
         context: C,
@@ -308,6 +372,18 @@ impl HotkeysRegistry<InputMode, Action> {
             Action::SelectPrevious,
         );
 
+        registry.register_system_hotkey(
+            InputMode::Normal,
+            &[KeyCombo::from(KeyCode::Tab)],
+            Action::SelectNextColumn,
+        );
+
+        registry.register_system_hotkey(
+            InputMode::Normal,
+            &[KeyCombo::from((KeyCode::BackTab, KeyModifiers::SHIFT))],
+            Action::SelectPreviousColumn,
+        );
+
         registry.register_system_hotkey(
             InputMode::Normal,
             &[KeyCombo::from(('d', KeyModifiers::CONTROL))],
@@ -326,6 +402,36 @@ impl HotkeysRegistry<InputMode, Action> {
             Action::ChangeDirectoryToSelectedEntry,
         );
 
+        registry.register_system_hotkey(
+            InputMode::Normal,
+            &[KeyCombo::from(('L', KeyModifiers::SHIFT))],
+            Action::EnterSelectedEntryAndExit,
+        );
+
+        registry.register_system_hotkey(
+            InputMode::Normal,
+            &[KeyCombo::from('}')],
+            Action::ChangeDirectoryToNextSibling,
+        );
+
+        registry.register_system_hotkey(
+            InputMode::Normal,
+            &[KeyCombo::from('{')],
+            Action::ChangeDirectoryToPreviousSibling,
+        );
+
+        registry.register_system_hotkey(
+            InputMode::Normal,
+            &[KeyCombo::from(('R', KeyModifiers::SHIFT))],
+            Action::ToggleRepositoryRoot,
+        );
+
+        registry.register_system_hotkey(
+            InputMode::Normal,
+            &[KeyCombo::from(('T', KeyModifiers::SHIFT))],
+            Action::OpenShellInSelectedEntry,
+        );
+
         registry.register_system_hotkey(
             InputMode::Normal,
             &[KeyCombo::from('h')],
@@ -350,6 +456,12 @@ impl HotkeysRegistry<InputMode, Action> {
             Action::SwitchToListMode(ListMode::Frecent),
         );
 
+        registry.register_system_hotkey(
+            InputMode::Normal,
+            &[KeyCombo::from(('b', KeyModifiers::CONTROL))],
+            Action::SwitchToListMode(ListMode::Bookmark),
+        );
+
         registry.register_system_hotkey(
             InputMode::Normal,
             &[KeyCombo::from('?')],
@@ -362,6 +474,18 @@ impl HotkeysRegistry<InputMode, Action> {
             Action::SwitchToInputMode(InputMode::Search),
         );
 
+        registry.register_system_hotkey(
+            InputMode::Normal,
+            &[KeyCombo::from(('v', KeyModifiers::CONTROL))],
+            Action::PasteSearchQuery,
+        );
+
+        registry.register_system_hotkey(
+            InputMode::Normal,
+            &[KeyCombo::from(':')],
+            Action::OpenCommandPalette,
+        );
+
         registry.register_system_hotkey(
             InputMode::Normal,
             &[KeyCombo::from(KeyCode::Esc)],
@@ -370,6 +494,12 @@ impl HotkeysRegistry<InputMode, Action> {
 
         registry.register_system_hotkey(InputMode::Normal, &[KeyCombo::from('q')], Action::Exit);
 
+        registry.register_system_hotkey(
+            InputMode::Normal,
+            &[KeyCombo::from(('q', KeyModifiers::CONTROL))],
+            Action::ExitWithoutChangingDirectory,
+        );
+
         registry.register_system_hotkey(
             InputMode::Normal,
             &[KeyCombo::from(KeyCode::Enter)],
@@ -382,6 +512,115 @@ impl HotkeysRegistry<InputMode, Action> {
             Action::ResetSearchInput,
         );
 
+        registry.register_system_hotkey(
+            InputMode::Normal,
+            &[KeyCombo::from('.')],
+            Action::ToggleHiddenFiles,
+        );
+
+        registry.register_system_hotkey(
+            InputMode::Normal,
+            &[KeyCombo::from(('H', KeyModifiers::SHIFT))],
+            Action::ToggleQuickJumpHints,
+        );
+
+        registry.register_system_hotkey(
+            InputMode::Normal,
+            &[KeyCombo::from(('I', KeyModifiers::SHIFT))],
+            Action::ToggleGitIgnoredOnly,
+        );
+
+        registry.register_system_hotkey(
+            InputMode::Normal,
+            &[KeyCombo::from(('P', KeyModifiers::SHIFT))],
+            Action::TogglePathBasenames,
+        );
+
+        registry.register_system_hotkey(
+            InputMode::Normal,
+            &[KeyCombo::from(('O', KeyModifiers::SHIFT))],
+            Action::ToggleSortDirection,
+        );
+
+        registry.register_system_hotkey(
+            InputMode::Normal,
+            &[KeyCombo::from(('F', KeyModifiers::SHIFT))],
+            Action::CycleDirectoryGrouping,
+        );
+
+        registry.register_system_hotkey(
+            InputMode::Normal,
+            &[KeyCombo::from(('M', KeyModifiers::SHIFT))],
+            Action::PushCurrentDirectoryToStack,
+        );
+
+        registry.register_system_hotkey(
+            InputMode::Normal,
+            &[KeyCombo::from('*')],
+            Action::TogglePinnedEntry,
+        );
+
+        registry.register_system_hotkey(
+            InputMode::Normal,
+            &[KeyCombo::from(('B', KeyModifiers::SHIFT))],
+            Action::ToggleBookmark,
+        );
+
+        for slot in 1..=SLOTS_COUNT {
+            let digit = char::from_digit(slot as u32, 10).unwrap();
+
+            registry.register_system_hotkey(
+                InputMode::Normal,
+                &[
+                    KeyCombo::from(('s', KeyModifiers::CONTROL)),
+                    KeyCombo::from(digit),
+                ],
+                Action::SetFavoriteSlot(slot),
+            );
+
+            registry.register_system_hotkey(
+                InputMode::Normal,
+                &[KeyCombo::from('\''), KeyCombo::from(digit)],
+                Action::ChangeDirectoryToFavoriteSlot(slot),
+            );
+        }
+
+        registry.register_system_hotkey(
+            InputMode::Normal,
+            &[KeyCombo::from(('D', KeyModifiers::SHIFT))],
+            Action::ComputeSelectedDirectorySize,
+        );
+
+        registry.register_system_hotkey(
+            InputMode::Normal,
+            &[KeyCombo::from(('Y', KeyModifiers::SHIFT))],
+            Action::CopySelectedEntryPath,
+        );
+
+        registry.register_system_hotkey(
+            InputMode::Normal,
+            &[KeyCombo::from(('y', KeyModifiers::CONTROL))],
+            Action::CopySelectedEntryRelativePath,
+        );
+
+        registry.register_system_hotkey(
+            InputMode::Normal,
+            &[KeyCombo::from(('N', KeyModifiers::SHIFT))],
+            Action::CopySelectedEntryName,
+        );
+
+        registry.register_system_hotkey(
+            InputMode::Normal,
+            &[KeyCombo::from(('U', KeyModifiers::SHIFT))],
+            Action::CopySelectedEntryUri,
+        );
+
+        registry.register_system_hotkey(
+            InputMode::Normal,
+            &[KeyCombo::from(('S', KeyModifiers::SHIFT))],
+            Action::ToggleSessionStats,
+        );
+
         registry.register_system_hotkey(
             InputMode::Search,
             &[KeyCombo::from(KeyCode::Enter)],
@@ -406,10 +645,52 @@ impl HotkeysRegistry<InputMode, Action> {
             Action::SelectPrevious,
         );
 
+        registry.register_system_hotkey(
+            InputMode::Search,
+            &[KeyCombo::from(('p', KeyModifiers::CONTROL))],
+            Action::RecallPreviousSearch,
+        );
+
+        registry.register_system_hotkey(
+            InputMode::Search,
+            &[KeyCombo::from(('n', KeyModifiers::CONTROL))],
+            Action::RecallNextSearch,
+        );
+
+        registry.register_system_hotkey(
+            InputMode::Search,
+            &[KeyCombo::from(('g', KeyModifiers::CONTROL))],
+            Action::ToggleContentSearch,
+        );
+
+        registry.register_system_hotkey(
+            InputMode::Search,
+            &[KeyCombo::from(('x', KeyModifiers::CONTROL))],
+            Action::ToggleInvertedSearch,
+        );
+
+        registry.register_system_hotkey(
+            InputMode::Search,
+            &[KeyCombo::from(('v', KeyModifiers::CONTROL))],
+            Action::PasteSearchQuery,
+        );
+
+        registry.register_system_hotkey(
+            InputMode::Search,
+            &[KeyCombo::from(KeyCode::Tab)],
+            Action::DrillIntoFirstDirectory,
+        );
+
         registry.register_system_hotkey(
             InputMode::Search,
             &[KeyCombo::from(KeyCode::Esc)],
-            Action::ExitSearchInput,
+            Action::AcceptSearchInput,
+        );
+
+        registry.register_system_hotkey(
+            InputMode::Search,
+            &[KeyCombo::from(('c', KeyModifiers::CONTROL))],
+            Action::CancelSearchInput,
         );
 
         registry.register_system_hotkey(
@@ -564,6 +845,29 @@ mod tests {
         );
     }
 
+    #[test]
+    fn hotkeys_trie_get_sequences_works_correctly() {
+        let mut trie = HotkeysTrie::new();
+        trie.insert(&[KeyCombo::from('a'), KeyCombo::from('b')], 1);
+        trie.insert(&[KeyCombo::from('a')], 2);
+        trie.insert(&[KeyCombo::from(('c', KeyModifiers::CONTROL))], 3);
+
+        let mut sequences: Vec<(String, i32)> = trie
+            .get_sequences()
+            .into_iter()
+            .map(|(key_combos, &value)| {
+                let keys: Vec<String> = key_combos.iter().map(|x| x.to_string()).collect();
+                (keys.join(" "), value)
+            })
+            .collect();
+        sequences.sort();
+
+        assert_eq!(
+            sequences,
+            vec![("Ctrl + c".into(), 3), ("a".into(), 2), ("a b".into(), 1)]
+        );
+    }
+
     #[test]
     fn hotkeys_trie_clear_works_correctly() {
         let mut trie = HotkeysTrie::new();
@@ -707,35 +1011,65 @@ mod tests {
         let entries = [
             Entry {
                 name: "s-dir1".into(),
+                name_lower: "s-dir1".into(),
                 kind: EntryKind::Directory,
                 path: PathBuf::from("/home/user/s-dir/"),
+                is_symlink: false,
+                is_executable: false,
+                permissions: None,
+                owner_ids: None,
             },
             Entry {
                 name: "d-dir2".into(),
+                name_lower: "d-dir2".into(),
                 kind: EntryKind::Directory,
                 path: PathBuf::from("/home/user/d-dir/"),
+                is_symlink: false,
+                is_executable: false,
+                permissions: None,
+                owner_ids: None,
             },
             Entry {
                 name: "w-dir3".into(),
+                name_lower: "w-dir3".into(),
                 kind: EntryKind::Directory,
                 path: PathBuf::from("/home/user/w-dir/"),
+                is_symlink: false,
+                is_executable: false,
+                permissions: None,
+                owner_ids: None,
             },
             Entry {
                 name: "e-dir4".into(),
+                name_lower: "e-dir4".into(),
                 kind: EntryKind::Directory,
                 path: PathBuf::from("/home/user/e-dir/"),
+                is_symlink: false,
+                is_executable: false,
+                permissions: None,
+                owner_ids: None,
             },
             Entry {
                 name: "r-dir5".into(),
+                name_lower: "r-dir5".into(),
                 kind: EntryKind::Directory,
                 path: PathBuf::from("/home/user/Cargo.toml"),
+                is_symlink: false,
+                is_executable: false,
+                permissions: None,
+                owner_ids: None,
             },
             Entry {
                 name: "Cargo.toml".into(),
+                name_lower: "cargo.toml".into(),
                 kind: EntryKind::File {
                     extension: Some("toml".into()),
                 },
                 path: PathBuf::from("/home/user/Cargo.toml"),
+                is_symlink: false,
+                is_executable: false,
+                permissions: None,
+                owner_ids: None,
             },
         ];
 
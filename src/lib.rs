@@ -1,3 +1,24 @@
 pub mod app;
+#[cfg(feature = "archives")]
+pub mod archive;
+pub mod bookmarks;
+pub mod cli;
+pub mod clipboard;
+pub mod command_palette;
+pub mod config;
+pub mod content_search;
+pub mod dir_size;
+pub mod dir_stack;
+pub mod directory_config;
 pub mod entry;
+pub mod favorites;
+pub mod hooks;
 pub mod hotkeys;
+pub mod metadata_loader;
+pub mod navigation_log;
+pub mod owners;
+pub mod path_lines;
+pub mod pins;
+pub mod shell;
+pub mod terminal;
+pub mod theme;
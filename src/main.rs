@@ -1,33 +1,81 @@
-use std::{io, path::PathBuf};
+use std::{
+    io::{self, Write},
+    path::{Path, PathBuf},
+};
+
+use anyhow::Context;
+use clap::Parser;
+use crossterm::terminal;
+use ratatui::{layout::Position, Terminal, TerminalOptions, Viewport};
 
-use crossterm::{
-    cursor, execute,
-    terminal::{self, EnterAlternateScreen, LeaveAlternateScreen},
+use tiny_fe::{
+    app::{App, RunOutcome},
+    cli::{generate_completions, Cli, Command},
+    config::Config,
+    dir_stack::DirectoryStack,
+    entry::read_paths,
+    shell::{integration_script, write_export_line},
 };
 
-use tiny_fe::app::{App, ListMode};
+/// The exit code that tells the shell integration to start a subshell in the printed directory
+const OPEN_SHELL_EXIT_CODE: i32 = 3;
+
+/// The number of rows the app takes when it's rendered inline (see `--no-alt-screen`)
+const INLINE_VIEWPORT_HEIGHT: u16 = 20;
 
 fn main() -> anyhow::Result<()> {
-    // Enter the alternate screen and hide the cursor
-    execute!(io::stderr(), EnterAlternateScreen)?;
-    execute!(io::stderr(), cursor::Hide)?;
+    let cli = Cli::parse();
+
+    if let Some(command) = &cli.command {
+        return run_command(&cli, command);
+    }
+
+    let config = Config::from(&cli);
+
+    // The paths have to be read before the terminal is set up, the keys are still read from the
+    // terminal afterwards
+    let piped_paths = if cli.stdin {
+        Some(read_paths(io::stdin().lock())?)
+    } else {
+        None
+    };
+
+    // Save the terminal title so that we can restore it on exit
+    if config.set_terminal_title {
+        tiny_fe::terminal::push_title(&mut io::stderr())?;
+    }
+
+    // Enter the alternate screen (unless rendering inline) and hide the cursor
+    let alternate_screen = !cli.no_alt_screen;
+    tiny_fe::terminal::enter_screen(&mut io::stderr(), alternate_screen)?;
 
     // Enable raw mode
     terminal::enable_raw_mode()?;
 
-    let result = run_app_ui();
+    let set_terminal_title = config.set_terminal_title;
+    let result = run_app_ui(config, piped_paths, alternate_screen);
 
     // Restore the terminal state
     terminal::disable_raw_mode()?;
 
     // Leave the alternate screen and show the cursor
-    execute!(io::stderr(), cursor::Show)?;
-    execute!(io::stderr(), LeaveAlternateScreen)?;
+    tiny_fe::terminal::leave_screen(&mut io::stderr(), alternate_screen)?;
+
+    if set_terminal_title {
+        tiny_fe::terminal::pop_title(&mut io::stderr())?;
+    }
 
     match result {
-        Ok(path) => {
-            println!("{}", path.display());
+        Ok(RunOutcome::ChangedTo(path) | RunOutcome::SelectedFile(path)) => {
+            print_result(&path, cli.export_var.as_deref())?;
         }
+        Ok(RunOutcome::OpenShellIn(path)) => {
+            print_result(&path, cli.export_var.as_deref())?;
+            io::stdout().flush()?;
+            std::process::exit(OPEN_SHELL_EXIT_CODE);
+        }
+        // Print nothing so that the shell integration knows it shouldn't change the directory
+        Ok(RunOutcome::Cancelled) => {}
         Err(err) => {
             eprintln!("Error: {}", err);
         }
@@ -36,12 +84,72 @@ fn main() -> anyhow::Result<()> {
     Ok(())
 }
 
-fn run_app_ui() -> anyhow::Result<PathBuf> {
-    let mut app = App::try_new(ListMode::default())?;
+fn run_command(cli: &Cli, command: &Command) -> anyhow::Result<()> {
+    match command {
+        Command::Popd => {
+            let file_path = cli
+                .get_directory_stack_file()
+                .context("Could not determine the directory stack file, use --stack-file")?;
+
+            if let Some(path) = DirectoryStack::load_from_disk(file_path)?.pop()? {
+                print_path(&path)?;
+            }
+        }
+        Command::Init { shell } => print!("{}", integration_script(*shell)),
+        Command::Completions { shell } => generate_completions(*shell, &mut io::stdout()),
+    }
+
+    Ok(())
+}
+
+/// Prints the path followed by a new line. The path is written as is, rather than through
+/// `display`, so that names that aren't valid UTF-8 reach the shell intact.
+fn print_path(path: &Path) -> io::Result<()> {
+    let mut stdout = io::stdout().lock();
+    stdout.write_all(path.as_os_str().as_encoded_bytes())?;
+    stdout.write_all(b"\n")
+}
+
+/// Prints the path the app has exited with, either as is or as an `export` line (see
+/// `--export-var`).
+fn print_result(path: &Path, export_var: Option<&str>) -> io::Result<()> {
+    match export_var {
+        Some(name) => write_export_line(&mut io::stdout().lock(), name, path),
+        None => print_path(path),
+    }
+}
+
+fn run_app_ui(
+    config: Config,
+    piped_paths: Option<Vec<PathBuf>>,
+    alternate_screen: bool,
+) -> anyhow::Result<RunOutcome> {
+    let mut app = match piped_paths {
+        Some(paths) => App::try_from_paths(paths, config)?,
+        None => App::try_new(config.start_list_mode, config)?,
+    };
 
     // Initialize the terminal backend
     let backend = ratatui::backend::CrosstermBackend::new(io::stderr());
-    let mut terminal = ratatui::Terminal::new(backend)?;
 
-    app.run(&mut terminal)
+    if alternate_screen {
+        let mut terminal = Terminal::new(backend)?;
+        return app.run(&mut terminal);
+    }
+
+    let mut terminal = Terminal::with_options(
+        backend,
+        TerminalOptions {
+            viewport: Viewport::Inline(INLINE_VIEWPORT_HEIGHT),
+        },
+    )?;
+
+    let result = app.run(&mut terminal);
+
+    // Put the cursor below the last frame, so that the prompt doesn't draw over it
+    let area = terminal.get_frame().area();
+    terminal.set_cursor_position(Position::new(0, area.bottom().saturating_sub(1)))?;
+    eprintln!();
+
+    result
 }
@@ -0,0 +1,124 @@
+//! Reads the metadata of the entries in the background. Reading the metadata of every entry can
+//! hang on network (or otherwise slow) file systems, so for the paths that are configured as slow
+//! the entries are listed right away and their metadata fills in as it arrives.
+
+use std::{
+    collections::HashSet,
+    path::{Path, PathBuf},
+    sync::mpsc::{self, Receiver, TryRecvError},
+    thread,
+};
+
+use crate::entry::UnixMetadata;
+
+/// Reads the metadata of a single path, it runs on the background thread
+pub type MetadataReader = fn(&Path) -> Option<UnixMetadata>;
+
+#[derive(Debug)]
+pub struct MetadataLoader {
+    receiver: Receiver<(PathBuf, Option<UnixMetadata>)>,
+
+    /// The paths whose metadata hasn't been received yet
+    pending: HashSet<PathBuf>,
+}
+
+impl MetadataLoader {
+    /// Starts reading the metadata of the given paths on a background thread
+    pub fn spawn(paths: Vec<PathBuf>, reader: MetadataReader) -> Self {
+        let (sender, receiver) = mpsc::channel();
+        let pending = paths.iter().cloned().collect();
+
+        thread::spawn(move || {
+            for path in paths {
+                let metadata = reader(&path);
+
+                // The receiver is gone once the user has moved on to another directory
+                if sender.send((path, metadata)).is_err() {
+                    break;
+                }
+            }
+        });
+
+        MetadataLoader { receiver, pending }
+    }
+
+    pub fn is_pending<T: AsRef<Path>>(&self, path: T) -> bool {
+        self.pending.contains(path.as_ref())
+    }
+
+    pub fn is_done(&self) -> bool {
+        self.pending.is_empty()
+    }
+
+    /// Returns the metadata that has been read since the last call, without waiting for more
+    pub fn try_receive(&mut self) -> Vec<(PathBuf, Option<UnixMetadata>)> {
+        let mut received = Vec::new();
+
+        loop {
+            match self.receiver.try_recv() {
+                Ok(x) => received.push(x),
+                Err(TryRecvError::Empty) => break,
+                // The thread is done, if it didn't send everything (it panicked) there's nothing
+                // left to wait for anyway
+                Err(TryRecvError::Disconnected) => {
+                    self.pending.clear();
+                    break;
+                }
+            }
+        }
+
+        for (path, _) in received.iter() {
+            self.pending.remove(path);
+        }
+
+        received
+    }
+}
+
+/// Returns true if the path is located under one of the slow prefixes
+pub fn is_slow_path<T: AsRef<Path>>(path: T, slow_prefixes: &[PathBuf]) -> bool {
+    slow_prefixes.iter().any(|x| path.as_ref().starts_with(x))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, Instant};
+
+    use super::*;
+
+    #[test]
+    fn is_slow_path_matches_prefixes() {
+        let slow_prefixes = vec![PathBuf::from("/mnt/nas"), PathBuf::from("/net")];
+
+        assert!(is_slow_path("/mnt/nas", &slow_prefixes));
+        assert!(is_slow_path("/mnt/nas/photos", &slow_prefixes));
+        assert!(!is_slow_path("/mnt/nasty", &slow_prefixes));
+        assert!(!is_slow_path("/home/user", &slow_prefixes));
+    }
+
+    #[test]
+    fn metadata_loader_receives_metadata_of_all_paths() {
+        let mut loader =
+            MetadataLoader::spawn(vec![PathBuf::from("/a"), PathBuf::from("/b")], |path| {
+                (path == Path::new("/a")).then_some((0o100644, (1, 2)))
+            });
+
+        assert!(loader.is_pending("/a"));
+
+        let start = Instant::now();
+        let mut received = Vec::new();
+
+        while !loader.is_done() && start.elapsed() < Duration::from_secs(5) {
+            received.extend(loader.try_receive());
+        }
+
+        assert_eq!(
+            received,
+            vec![
+                (PathBuf::from("/a"), Some((0o100644, (1, 2)))),
+                (PathBuf::from("/b"), None)
+            ]
+        );
+        assert!(!loader.is_pending("/a"));
+    }
+}
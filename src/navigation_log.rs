@@ -0,0 +1,103 @@
+//! An append-only log of the directories visited in the app (see `--log`), for users that want to
+//! look back at where they've been. The app only ever writes to it.
+//!
+//! Every visit is a line with the Unix timestamp (in seconds) and the path separated by a tab,
+//! e.g. `1729000000<TAB>/home/user/projects`.
+
+use std::{
+    fs::{self, File, OpenOptions},
+    io::{self, BufWriter, Write},
+    path::Path,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use anyhow::Context;
+
+#[derive(Debug)]
+pub struct NavigationLog {
+    /// The visits are buffered, they're written out once enough of them pile up and when the log
+    /// is dropped (i.e. when the app exits)
+    writer: BufWriter<File>,
+}
+
+impl NavigationLog {
+    /// Opens the log for appending, the file (and its parent directories) are created if needed.
+    pub fn open<T: AsRef<Path>>(file_path: T) -> anyhow::Result<Self> {
+        let file_path = file_path.as_ref();
+
+        if let Some(parent) = file_path.parent() {
+            fs::create_dir_all(parent)
+                .with_context(|| format!("Failed to create the directory {}", parent.display()))?;
+        }
+
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(file_path)
+            .with_context(|| format!("Failed to open {}", file_path.display()))?;
+
+        Ok(NavigationLog {
+            writer: BufWriter::new(file),
+        })
+    }
+
+    /// Records a visit of the given directory at the current time
+    pub fn record<T: AsRef<Path>>(&mut self, path: T) -> io::Result<()> {
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |x| x.as_secs());
+
+        write_line(&mut self.writer, timestamp, path.as_ref())
+    }
+
+    /// Writes out the buffered visits
+    pub fn flush(&mut self) -> io::Result<()> {
+        self.writer.flush()
+    }
+}
+
+/// Writes a single line of the log. The path is written as is, since it isn't necessarily valid
+/// UTF-8.
+fn write_line<W: Write>(writer: &mut W, timestamp: u64, path: &Path) -> io::Result<()> {
+    write!(writer, "{timestamp}\t")?;
+    writer.write_all(path.as_os_str().as_encoded_bytes())?;
+    writer.write_all(b"\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn write_line_separates_timestamp_and_path_with_a_tab() {
+        let mut output = Vec::new();
+        write_line(&mut output, 1729000000, Path::new("/home/user")).unwrap();
+
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            "1729000000\t/home/user\n"
+        );
+    }
+
+    #[test]
+    fn navigation_log_appends_to_existing_file() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let file_path = temp_dir.path().join("logs").join("navigation");
+
+        let mut log = NavigationLog::open(&file_path).unwrap();
+        log.record("/home/user").unwrap();
+        drop(log);
+
+        let mut log = NavigationLog::open(&file_path).unwrap();
+        log.record("/tmp").unwrap();
+        log.flush().unwrap();
+
+        let contents = fs::read_to_string(&file_path).unwrap();
+        let paths: Vec<_> = contents
+            .lines()
+            .map(|x| x.split_once('\t').unwrap().1)
+            .collect();
+
+        assert_eq!(paths, vec!["/home/user", "/tmp"]);
+    }
+}
@@ -0,0 +1,77 @@
+//! Resolves the owners (user and group) of the entries to their names. Looking up a name can hit
+//! the network (NIS, LDAP, ...) on some systems, so every id is resolved only once.
+
+use std::collections::HashMap;
+
+#[derive(Debug, Default)]
+pub struct OwnerNames {
+    users: HashMap<u32, String>,
+    groups: HashMap<u32, String>,
+}
+
+impl OwnerNames {
+    /// Returns the owner as `user:group`, ids without a name are shown as they are
+    pub fn get_owner(&mut self, uid: u32, gid: u32) -> String {
+        let user = self
+            .users
+            .entry(uid)
+            .or_insert_with(|| lookup_user_name(uid).unwrap_or_else(|| uid.to_string()));
+
+        let group = self
+            .groups
+            .entry(gid)
+            .or_insert_with(|| lookup_group_name(gid).unwrap_or_else(|| gid.to_string()));
+
+        format!("{user}:{group}")
+    }
+}
+
+#[cfg(unix)]
+fn lookup_user_name(uid: u32) -> Option<String> {
+    uzers::get_user_by_uid(uid).map(|x| x.name().to_string_lossy().into_owned())
+}
+
+#[cfg(not(unix))]
+fn lookup_user_name(_uid: u32) -> Option<String> {
+    None
+}
+
+#[cfg(unix)]
+fn lookup_group_name(gid: u32) -> Option<String> {
+    uzers::get_group_by_gid(gid).map(|x| x.name().to_string_lossy().into_owned())
+}
+
+#[cfg(not(unix))]
+fn lookup_group_name(_gid: u32) -> Option<String> {
+    None
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn get_owner_resolves_current_user() {
+        let mut owner_names = OwnerNames::default();
+        let user = uzers::get_current_username().unwrap();
+        let group = uzers::get_current_groupname().unwrap();
+
+        assert_eq!(
+            owner_names.get_owner(uzers::get_current_uid(), uzers::get_current_gid()),
+            format!("{}:{}", user.to_string_lossy(), group.to_string_lossy())
+        );
+        assert_eq!(owner_names.users.len(), 1);
+        assert_eq!(owner_names.groups.len(), 1);
+    }
+
+    #[test]
+    fn get_owner_falls_back_to_ids() {
+        let mut owner_names = OwnerNames::default();
+
+        // Nobody should be using these
+        assert_eq!(
+            owner_names.get_owner(4_000_000_000, 4_000_000_001),
+            "4000000000:4000000001"
+        );
+    }
+}
@@ -0,0 +1,198 @@
+//! Plain text files with one path per line, the way the directory stack, the pins and the other
+//! small stores are persisted. The paths are stored as raw bytes, since they aren't necessarily
+//! valid UTF-8.
+
+use std::{
+    ffi::OsString,
+    fs,
+    io::ErrorKind,
+    path::{Path, PathBuf},
+};
+
+use anyhow::Context;
+
+/// Reads the paths from the given file, a missing file is treated as an empty one. Empty lines
+/// are skipped.
+pub fn read_path_lines<T: AsRef<Path>>(file_path: T) -> anyhow::Result<Vec<PathBuf>> {
+    Ok(read_path_slots(file_path)?.into_iter().flatten().collect())
+}
+
+/// Reads the lines of the given file as slots, an empty line being an unset slot (see the
+/// favorites). A missing file is treated as an empty one.
+pub fn read_path_slots<T: AsRef<Path>>(file_path: T) -> anyhow::Result<Vec<Option<PathBuf>>> {
+    let file_path = file_path.as_ref();
+
+    let mut contents = match fs::read(file_path) {
+        Ok(contents) => contents,
+        Err(err) if err.kind() == ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(err) => {
+            return Err(err).with_context(|| format!("Failed to read {}", file_path.display()))
+        }
+    };
+
+    // The last line is terminated as well, which isn't an extra empty line
+    if contents.last() == Some(&b'\n') {
+        contents.pop();
+    }
+
+    if contents.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let slots = contents
+        .split(|&x| x == b'\n')
+        .map(|x| (!x.is_empty()).then(|| PathBuf::from(os_string_from_bytes(x))))
+        .collect();
+
+    Ok(slots)
+}
+
+/// Writes the paths to the given file, one per line, creating its parent directories if needed
+/// (a custom file, e.g. `~/.config/tiny-fe/stack`, might live in a directory that doesn't exist
+/// yet).
+pub fn write_path_lines<T: AsRef<Path>, P: AsRef<Path>>(
+    file_path: T,
+    paths: &[P],
+) -> anyhow::Result<()> {
+    write_lines(file_path.as_ref(), paths.iter().map(|x| Some(x.as_ref())))
+}
+
+/// Writes the slots to the given file, one per line, an unset slot being an empty line
+pub fn write_path_slots<T: AsRef<Path>, P: AsRef<Path>>(
+    file_path: T,
+    slots: &[Option<P>],
+) -> anyhow::Result<()> {
+    write_lines(
+        file_path.as_ref(),
+        slots.iter().map(|x| x.as_ref().map(|x| x.as_ref())),
+    )
+}
+
+fn write_lines<'a>(
+    file_path: &Path,
+    lines: impl Iterator<Item = Option<&'a Path>>,
+) -> anyhow::Result<()> {
+    let mut contents = Vec::new();
+
+    for line in lines {
+        if let Some(path) = line {
+            contents.extend_from_slice(path.as_os_str().as_encoded_bytes());
+        }
+
+        contents.push(b'\n');
+    }
+
+    if let Some(parent) = file_path.parent() {
+        fs::create_dir_all(parent)
+            .with_context(|| format!("Failed to create the directory {}", parent.display()))?;
+    }
+
+    fs::write(file_path, contents)
+        .with_context(|| format!("Failed to write {}", file_path.display()))?;
+
+    Ok(())
+}
+
+#[cfg(unix)]
+fn os_string_from_bytes(bytes: &[u8]) -> OsString {
+    use std::os::unix::ffi::OsStrExt;
+
+    std::ffi::OsStr::from_bytes(bytes).to_os_string()
+}
+
+#[cfg(not(unix))]
+fn os_string_from_bytes(bytes: &[u8]) -> OsString {
+    String::from_utf8_lossy(bytes).into_owned().into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn path_lines_are_read_back_as_written() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let file_path = temp_dir.path().join("paths");
+
+        write_path_lines(&file_path, &["/home/user/first", "/home/user/second"]).unwrap();
+
+        assert_eq!(
+            fs::read_to_string(&file_path).unwrap(),
+            "/home/user/first\n/home/user/second\n"
+        );
+        assert_eq!(
+            read_path_lines(&file_path).unwrap(),
+            [
+                PathBuf::from("/home/user/first"),
+                PathBuf::from("/home/user/second")
+            ]
+        );
+    }
+
+    #[test]
+    fn read_path_lines_skips_empty_lines() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let file_path = temp_dir.path().join("paths");
+        fs::write(&file_path, "\n/tmp\n\n").unwrap();
+
+        assert_eq!(
+            read_path_lines(&file_path).unwrap(),
+            [PathBuf::from("/tmp")]
+        );
+    }
+
+    #[test]
+    fn path_slots_keep_unset_slots_as_empty_lines() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let file_path = temp_dir.path().join("slots");
+
+        write_path_slots(&file_path, &[Some("/home/user"), None, Some("/tmp"), None]).unwrap();
+
+        assert_eq!(
+            fs::read_to_string(&file_path).unwrap(),
+            "/home/user\n\n/tmp\n\n"
+        );
+        assert_eq!(
+            read_path_slots(&file_path).unwrap(),
+            [
+                Some(PathBuf::from("/home/user")),
+                None,
+                Some(PathBuf::from("/tmp")),
+                None
+            ]
+        );
+    }
+
+    #[test]
+    fn read_path_lines_treats_missing_file_as_empty() {
+        let temp_dir = tempfile::tempdir().unwrap();
+
+        assert!(read_path_lines(temp_dir.path().join("missing"))
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn write_path_lines_creates_missing_parent_directories() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let file_path = temp_dir.path().join("config").join("tiny-fe").join("stack");
+
+        write_path_lines(&file_path, &["/home/user"]).unwrap();
+
+        assert_eq!(fs::read_to_string(&file_path).unwrap(), "/home/user\n");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn path_lines_keep_paths_that_are_not_valid_utf8() {
+        use std::{ffi::OsStr, os::unix::ffi::OsStrExt};
+
+        let temp_dir = tempfile::tempdir().unwrap();
+        let file_path = temp_dir.path().join("paths");
+        let path = Path::new("/home/user").join(OsStr::from_bytes(b"caf\xe9"));
+
+        write_path_lines(&file_path, &[&path]).unwrap();
+
+        assert_eq!(read_path_lines(&file_path).unwrap(), [path]);
+    }
+}
@@ -0,0 +1,86 @@
+//! Entries pinned to the top of their directory's listing.
+//!
+//! Pressing `*` pins (or unpins) the selected entry, pinned entries are listed before the rest of
+//! their directory regardless of their names. The pins are persisted in a plain text file with the
+//! full path of one pinned entry per line, the parent of each path is the directory it's pinned
+//! in.
+
+use std::{
+    collections::HashSet,
+    path::{Path, PathBuf},
+};
+
+use crate::path_lines::{read_path_lines, write_path_lines};
+
+#[derive(Debug, Default)]
+pub struct Pins {
+    /// The file the pins are persisted to, the pins aren't persisted without it
+    file_path: Option<PathBuf>,
+
+    /// The full paths of the pinned entries
+    paths: HashSet<PathBuf>,
+}
+
+impl Pins {
+    /// Loads the pins from the given file, a missing file is treated as no pins at all.
+    pub fn load_from_disk<T: AsRef<Path>>(file_path: T) -> anyhow::Result<Self> {
+        let file_path = file_path.as_ref().to_path_buf();
+        let paths = read_path_lines(&file_path)?.into_iter().collect();
+
+        Ok(Pins {
+            file_path: Some(file_path),
+            paths,
+        })
+    }
+
+    pub fn save_to_disk(&self) -> anyhow::Result<()> {
+        let Some(file_path) = &self.file_path else {
+            return Ok(());
+        };
+
+        // Sorted, so that the file doesn't change needlessly between saves
+        let mut paths: Vec<_> = self.paths.iter().collect();
+        paths.sort();
+
+        write_path_lines(file_path, &paths)
+    }
+
+    pub fn is_pinned(&self, path: &Path) -> bool {
+        self.paths.contains(path)
+    }
+
+    /// Pins the entry at the given path or unpins it when it's already pinned, and persists the
+    /// pins. Returns whether the entry is pinned now.
+    pub fn toggle<T: AsRef<Path>>(&mut self, path: T) -> anyhow::Result<bool> {
+        let path = path.as_ref();
+
+        let is_pinned = if self.paths.remove(path) {
+            false
+        } else {
+            self.paths.insert(path.to_path_buf());
+            true
+        };
+
+        self.save_to_disk()?;
+
+        Ok(is_pinned)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pins_toggle_pins_and_unpins() {
+        let mut pins = Pins::default();
+
+        assert!(pins.toggle("/home/user/zeta").unwrap());
+        assert!(pins.toggle("/home/user/alpha").unwrap());
+        assert!(pins.is_pinned(Path::new("/home/user/zeta")));
+
+        assert!(!pins.toggle("/home/user/zeta").unwrap());
+        assert!(!pins.is_pinned(Path::new("/home/user/zeta")));
+        assert!(pins.is_pinned(Path::new("/home/user/alpha")));
+    }
+}
@@ -0,0 +1,256 @@
+use std::{
+    io::{self, Write},
+    path::Path,
+};
+
+/// Parses the name of a shell variable, it has to be a valid POSIX name (letters, digits and
+/// underscores, not starting with a digit).
+pub fn parse_variable_name(value: &str) -> anyhow::Result<String> {
+    let is_valid = value.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_')
+        && value.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+
+    anyhow::ensure!(is_valid, "'{value}' isn't a valid shell variable name");
+
+    Ok(value.to_string())
+}
+
+/// Writes `export NAME="path"` followed by a new line, so that the output can be `eval`ed or
+/// `source`d. The characters that are special inside double quotes are escaped and the rest of the
+/// path is written as is, so that names that aren't valid UTF-8 reach the shell intact.
+pub fn write_export_line<W: Write>(writer: &mut W, name: &str, path: &Path) -> io::Result<()> {
+    let mut line = format!("export {name}=\"").into_bytes();
+
+    for &byte in path.as_os_str().as_encoded_bytes() {
+        if matches!(byte, b'"' | b'\\' | b'$' | b'`') {
+            line.push(b'\\');
+        }
+
+        line.push(byte);
+    }
+
+    line.extend_from_slice(b"\"\n");
+    writer.write_all(&line)
+}
+
+/// The shells `tiny-fe init` can print the integration for
+#[derive(Debug, Clone, Copy, PartialEq, clap::ValueEnum)]
+pub enum IntegrationShell {
+    Bash,
+    Zsh,
+    Fish,
+}
+
+/// The integration for bash and zsh, `3` is the exit code that asks for a subshell (see `T`).
+/// The variables mustn't clash with zsh's special parameters (e.g. `status` is read-only there).
+/// Only directories are entered, since some outcomes print a file (`L` on a file, `--stdin`).
+const POSIX_INTEGRATION_SCRIPT: &str = r#"tf() {
+    local dir code
+    dir="$(command tiny-fe "$@")"
+    code=$?
+
+    if [ -d "$dir" ]; then
+        cd "$dir" || return
+    fi
+
+    if [ "$code" -eq 3 ]; then
+        "${SHELL:-sh}"
+    elif [ "$code" -ne 0 ]; then
+        return "$code"
+    fi
+}
+
+tf-popd() {
+    local dir
+    dir="$(command tiny-fe popd)" && [ -n "$dir" ] && pushd "$dir"
+}
+"#;
+
+const FISH_INTEGRATION_SCRIPT: &str = r#"function tf
+    set -l dir (command tiny-fe $argv)
+    set -l exit_code $status
+
+    if test -d "$dir"
+        cd $dir; or return
+    end
+
+    if test $exit_code -eq 3
+        set -q SHELL; or set -l SHELL sh
+        $SHELL
+    else if test $exit_code -ne 0
+        return $exit_code
+    end
+end
+
+function tf-popd
+    set -l dir (command tiny-fe popd)
+    test -n "$dir"; and pushd $dir
+end
+"#;
+
+/// Returns the shell functions that `cd` into the directory the app exits with (`tf`) and pop the
+/// directory stack (`tf-popd`), meant to be `eval`ed (or `source`d in fish) from the shell's
+/// startup file.
+pub fn integration_script(shell: IntegrationShell) -> &'static str {
+    match shell {
+        IntegrationShell::Bash | IntegrationShell::Zsh => POSIX_INTEGRATION_SCRIPT,
+        IntegrationShell::Fish => FISH_INTEGRATION_SCRIPT,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_variable_name_works_correctly() {
+        assert_eq!(
+            parse_variable_name("TINY_DC_RESULT").unwrap(),
+            "TINY_DC_RESULT"
+        );
+        assert_eq!(parse_variable_name("_dir2").unwrap(), "_dir2");
+        assert!(parse_variable_name("").is_err());
+        assert!(parse_variable_name("2DIR").is_err());
+        assert!(parse_variable_name("MY-DIR").is_err());
+    }
+
+    #[test]
+    fn write_export_line_quotes_the_path() {
+        let mut output = Vec::new();
+        write_export_line(
+            &mut output,
+            "TINY_DC_RESULT",
+            Path::new("/home/user/my notes"),
+        )
+        .unwrap();
+
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            "export TINY_DC_RESULT=\"/home/user/my notes\"\n"
+        );
+    }
+
+    #[test]
+    fn integration_script_defines_the_functions() {
+        for shell in [
+            IntegrationShell::Bash,
+            IntegrationShell::Zsh,
+            IntegrationShell::Fish,
+        ] {
+            let script = integration_script(shell);
+
+            assert!(script.contains("command tiny-fe"));
+            assert!(script.contains("tf-popd"));
+        }
+    }
+
+    #[test]
+    fn posix_integration_script_avoids_zsh_special_parameters() {
+        let script = integration_script(IntegrationShell::Zsh);
+        let words: Vec<_> = script
+            .split(|c: char| !c.is_ascii_alphanumeric() && c != '_')
+            .collect();
+
+        for name in ["status", "path", "pipestatus", "argv", "cdpath", "fpath"] {
+            assert!(!words.contains(&name), "the script uses `{name}`");
+        }
+    }
+
+    /// Runs `tf` in the given shell (from within `start_dir`) with a stand-in `tiny-fe` that
+    /// prints the given path and exits with the given code. Returns the exit status of `tf` and
+    /// the directory the shell ends up in, `None` when the shell isn't installed.
+    #[cfg(unix)]
+    fn run_tf_in_shell(
+        shell: &str,
+        start_dir: &Path,
+        printed_path: &Path,
+        exit_code: i32,
+    ) -> Option<(i32, String)> {
+        use std::{os::unix::fs::PermissionsExt, process::Command};
+
+        let bin_dir = tempfile::tempdir().unwrap();
+        let fake_binary = bin_dir.path().join("tiny-fe");
+        std::fs::write(
+            &fake_binary,
+            "#!/bin/sh\necho \"$TF_TARGET\"\nexit \"$TF_EXIT_CODE\"\n",
+        )
+        .unwrap();
+        std::fs::set_permissions(&fake_binary, std::fs::Permissions::from_mode(0o755)).unwrap();
+
+        let path = format!(
+            "{}:{}",
+            bin_dir.path().display(),
+            std::env::var("PATH").unwrap_or_default()
+        );
+
+        let output = Command::new(shell)
+            .args(["-c", "eval \"$TF_SCRIPT\"; tf; echo \"$?\"; pwd"])
+            .current_dir(start_dir)
+            .env("PATH", path)
+            .env("TF_TARGET", printed_path)
+            .env("TF_EXIT_CODE", exit_code.to_string())
+            .env("TF_SCRIPT", POSIX_INTEGRATION_SCRIPT)
+            .output()
+            .ok()?;
+
+        assert!(
+            output.status.success(),
+            "{}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+
+        let stdout = String::from_utf8(output.stdout).unwrap();
+        let (tf_status, directory) = stdout.trim().split_once('\n').unwrap();
+
+        Some((tf_status.parse().unwrap(), directory.to_string()))
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn posix_integration_script_changes_directory() {
+        let start = tempfile::tempdir().unwrap();
+        let start_path = start.path().canonicalize().unwrap();
+        let target = tempfile::tempdir().unwrap();
+        let target_path = target.path().canonicalize().unwrap();
+        let file_path = target_path.join("notes.txt");
+        std::fs::write(&file_path, "").unwrap();
+
+        // Only the shells that are installed can be checked
+        for shell in ["bash", "zsh"] {
+            let Some(outcome) = run_tf_in_shell(shell, &start_path, &target_path, 0) else {
+                continue;
+            };
+            assert_eq!(
+                outcome,
+                (0, target_path.to_str().unwrap().to_string()),
+                "in {shell}"
+            );
+
+            // A file (e.g. `L` on a file) isn't entered
+            let outcome = run_tf_in_shell(shell, &start_path, &file_path, 0).unwrap();
+            assert_eq!(
+                outcome,
+                (0, start_path.to_str().unwrap().to_string()),
+                "in {shell}"
+            );
+
+            // A failure is passed on
+            let outcome = run_tf_in_shell(shell, &start_path, Path::new(""), 2).unwrap();
+            assert_eq!(
+                outcome,
+                (2, start_path.to_str().unwrap().to_string()),
+                "in {shell}"
+            );
+        }
+    }
+
+    #[test]
+    fn write_export_line_escapes_special_characters() {
+        let mut output = Vec::new();
+        write_export_line(&mut output, "DIR", Path::new("/tmp/\"$HOME\"/`x`\\")).unwrap();
+
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            "export DIR=\"/tmp/\\\"\\$HOME\\\"/\\`x\\`\\\\\"\n"
+        );
+    }
+}
@@ -0,0 +1,88 @@
+use std::io::{self, Write};
+
+use crossterm::{
+    cursor, execute,
+    terminal::{EnterAlternateScreen, LeaveAlternateScreen, SetTitle},
+};
+
+/// Prepares the terminal for drawing the app: hides the cursor and, unless the app is rendered
+/// inline (see `--no-alt-screen`), switches to the alternate screen.
+pub fn enter_screen<W: Write>(writer: &mut W, alternate_screen: bool) -> io::Result<()> {
+    if alternate_screen {
+        execute!(writer, EnterAlternateScreen)?;
+    }
+
+    execute!(writer, cursor::Hide)
+}
+
+/// Undoes `enter_screen`, an inline app is left in the scrollback.
+pub fn leave_screen<W: Write>(writer: &mut W, alternate_screen: bool) -> io::Result<()> {
+    execute!(writer, cursor::Show)?;
+
+    if alternate_screen {
+        execute!(writer, LeaveAlternateScreen)?;
+    }
+
+    Ok(())
+}
+
+/// Sets the title of the terminal window/tab.
+pub fn set_title<W: Write>(writer: &mut W, title: &str) -> io::Result<()> {
+    execute!(writer, SetTitle(title))
+}
+
+/// Saves the current terminal title on the terminal's title stack, so that it can be restored with
+/// `pop_title` when the app exits.
+pub fn push_title<W: Write>(writer: &mut W) -> io::Result<()> {
+    write!(writer, "\x1b[22;0t")?;
+    writer.flush()
+}
+
+/// Restores the terminal title that was saved with `push_title`.
+pub fn pop_title<W: Write>(writer: &mut W) -> io::Result<()> {
+    write!(writer, "\x1b[23;0t")?;
+    writer.flush()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn set_title_writes_osc_sequence() {
+        let mut output = Vec::new();
+        set_title(&mut output, "/home/user").unwrap();
+
+        assert_eq!(String::from_utf8(output).unwrap(), "\x1b]0;/home/user\x07");
+    }
+
+    #[test]
+    fn enter_and_leave_screen_switch_to_alternate_screen() {
+        let mut output = Vec::new();
+        enter_screen(&mut output, true).unwrap();
+        leave_screen(&mut output, true).unwrap();
+
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            "\x1b[?1049h\x1b[?25l\x1b[?25h\x1b[?1049l"
+        );
+    }
+
+    #[test]
+    fn enter_and_leave_screen_omit_alternate_screen_when_inline() {
+        let mut output = Vec::new();
+        enter_screen(&mut output, false).unwrap();
+        leave_screen(&mut output, false).unwrap();
+
+        assert_eq!(String::from_utf8(output).unwrap(), "\x1b[?25l\x1b[?25h");
+    }
+
+    #[test]
+    fn push_and_pop_title_write_title_stack_sequences() {
+        let mut output = Vec::new();
+        push_title(&mut output).unwrap();
+        pop_title(&mut output).unwrap();
+
+        assert_eq!(String::from_utf8(output).unwrap(), "\x1b[22;0t\x1b[23;0t");
+    }
+}
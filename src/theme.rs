@@ -0,0 +1,138 @@
+use std::{collections::HashMap, str::FromStr};
+
+use ratatui::style::{Color, Style, Stylize};
+
+/// The customizable styles used when rendering the app.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Theme {
+    /// The style of the part of an entry name that matches the search query
+    pub search_hit: Style,
+
+    /// The colors of files by their (lowercase) extension, files with other extensions use the
+    /// default file style
+    pub extension_colors: HashMap<String, Color>,
+
+    /// The prompt shown in front of the search query
+    pub search_prompt: String,
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Theme {
+            search_hit: Style::default().underlined(),
+            extension_colors: HashMap::new(),
+            search_prompt: String::from("/"),
+        }
+    }
+}
+
+impl Theme {
+    /// Returns the color configured for the given file extension, if any. A compound extension
+    /// (e.g. `tar.gz`) without a color of its own uses the color of its last part.
+    pub fn get_extension_color(&self, extension: &str) -> Option<Color> {
+        let extension = extension.to_lowercase();
+
+        self.extension_colors
+            .get(&extension)
+            .or_else(|| {
+                let (_, last) = extension.rsplit_once('.')?;
+                self.extension_colors.get(last)
+            })
+            .copied()
+    }
+}
+
+/// Parses an extension to color mapping in the form of `extension=color` (e.g. `rs=green`), the
+/// leading dot of the extension is optional.
+pub fn parse_extension_color(value: &str) -> anyhow::Result<(String, Color)> {
+    let (extension, color) = value
+        .split_once('=')
+        .ok_or_else(|| anyhow::anyhow!("'{value}' should be in the form of extension=color"))?;
+
+    let extension = extension.trim().trim_start_matches('.').to_lowercase();
+    let color = Color::from_str(color.trim())
+        .map_err(|_| anyhow::anyhow!("'{color}' isn't a valid color"))?;
+
+    anyhow::ensure!(!extension.is_empty(), "'{value}' is missing the extension");
+
+    Ok((extension, color))
+}
+
+/// Parses a style from a modifier name (`underline`, `bold` or `reverse`) or a foreground color
+/// (a color name such as `yellow` or a hex value such as `#ffaa00`).
+pub fn parse_style(value: &str) -> anyhow::Result<Style> {
+    let style = match value.to_lowercase().as_str() {
+        "underline" | "underlined" => Style::default().underlined(),
+        "bold" => Style::default().bold(),
+        "reverse" | "reversed" => Style::default().reversed(),
+        color => Style::default().fg(Color::from_str(color).map_err(|_| {
+            anyhow::anyhow!(
+                "'{value}' isn't a valid style, expected underline, bold, reverse or a color"
+            )
+        })?),
+    };
+
+    Ok(style)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_style_works_correctly() {
+        assert_eq!(
+            parse_style("underline").unwrap(),
+            Style::default().underlined()
+        );
+        assert_eq!(parse_style("Bold").unwrap(), Style::default().bold());
+        assert_eq!(parse_style("reverse").unwrap(), Style::default().reversed());
+        assert_eq!(parse_style("yellow").unwrap(), Style::default().yellow());
+        assert_eq!(
+            parse_style("#ffaa00").unwrap(),
+            Style::default().fg(Color::Rgb(255, 170, 0))
+        );
+        assert!(parse_style("sparkly").is_err());
+    }
+
+    #[test]
+    fn get_extension_color_falls_back_to_last_part_of_compound_extension() {
+        let theme = Theme {
+            extension_colors: HashMap::from([
+                ("gz".to_string(), Color::Red),
+                ("tar.xz".to_string(), Color::Blue),
+            ]),
+            ..Default::default()
+        };
+
+        assert_eq!(theme.get_extension_color("tar.gz"), Some(Color::Red));
+        assert_eq!(theme.get_extension_color("TAR.XZ"), Some(Color::Blue));
+        assert_eq!(theme.get_extension_color("xz"), None);
+    }
+
+    #[test]
+    fn parse_extension_color_works_correctly() {
+        assert_eq!(
+            parse_extension_color("rs=green").unwrap(),
+            ("rs".into(), Color::Green)
+        );
+        assert_eq!(
+            parse_extension_color(".MD=#0000ff").unwrap(),
+            ("md".into(), Color::Rgb(0, 0, 255))
+        );
+        assert!(parse_extension_color("rs").is_err());
+        assert!(parse_extension_color("=green").is_err());
+        assert!(parse_extension_color("rs=sparkly").is_err());
+    }
+
+    #[test]
+    fn get_extension_color_is_case_insensitive() {
+        let theme = Theme {
+            extension_colors: HashMap::from([("rs".into(), Color::Green)]),
+            ..Default::default()
+        };
+
+        assert_eq!(theme.get_extension_color("RS"), Some(Color::Green));
+        assert_eq!(theme.get_extension_color("md"), None);
+    }
+}
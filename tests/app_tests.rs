@@ -1,10 +1,17 @@
-use std::fs::{create_dir, File};
+use std::{
+    fs::{create_dir, File},
+    path::PathBuf,
+};
 
 use crossterm::event::{KeyCode, KeyModifiers};
 use insta::assert_snapshot;
 use ratatui::{backend::TestBackend, Terminal};
 
-use tiny_fe::app::App;
+use tiny_fe::{
+    app::{App, RunOutcome},
+    config::Config,
+    entry::{Entry, EntryKind},
+};
 
 #[test]
 fn change_directory_lists_correct_directory_entires() {
@@ -213,5 +220,195 @@ fn app_returns_expected_path_after_exit() {
     let result = app.run(&mut terminal).unwrap();
 
     // The app should return the path of the subdirectory since that's where we exited
-    assert_eq!(result, sub_dir);
+    assert_eq!(result, RunOutcome::ChangedTo(sub_dir));
+}
+
+#[test]
+fn always_show_entries_are_listed_when_hidden_files_are_hidden() {
+    // Create a temporary directory with a static name so that test snapshots are consistent
+    let temp_dir = tempfile::Builder::new()
+        .prefix("tiny_fe_always_show")
+        .rand_bytes(0)
+        .tempdir()
+        .unwrap();
+
+    let temp_path = temp_dir.path();
+
+    create_dir(temp_path.join(".git")).unwrap();
+    create_dir(temp_path.join(".cache")).unwrap();
+    create_dir(temp_path.join("sub_dir")).unwrap();
+    File::create(temp_path.join(".hidden_file")).unwrap();
+    File::create(temp_path.join("file_1.txt")).unwrap();
+
+    let mut app = App::new(Config {
+        hide_hidden_files: true,
+        always_show: vec![".git".into()],
+        ..Default::default()
+    });
+
+    app.change_directory(temp_path).unwrap();
+
+    let mut terminal = Terminal::new(TestBackend::new(80, 10)).unwrap();
+    terminal
+        .draw(|frame| frame.render_widget(&mut app, frame.area()))
+        .unwrap();
+
+    assert_snapshot!(terminal.backend());
+}
+
+#[test]
+fn always_show_entries_are_sorted_to_the_top() {
+    // Create a temporary directory with a static name so that test snapshots are consistent
+    let temp_dir = tempfile::Builder::new()
+        .prefix("tiny_fe_always_show_on_top")
+        .rand_bytes(0)
+        .tempdir()
+        .unwrap();
+
+    let temp_path = temp_dir.path();
+
+    create_dir(temp_path.join("a_dir")).unwrap();
+    create_dir(temp_path.join(".config")).unwrap();
+    File::create(temp_path.join(".hidden_file")).unwrap();
+    File::create(temp_path.join(".env")).unwrap();
+
+    let mut app = App::new(Config {
+        hide_hidden_files: true,
+        always_show: vec![".config".into(), ".env".into()],
+        always_show_on_top: true,
+        ..Default::default()
+    });
+
+    app.change_directory(temp_path).unwrap();
+
+    let mut terminal = Terminal::new(TestBackend::new(80, 10)).unwrap();
+    terminal
+        .draw(|frame| frame.render_widget(&mut app, frame.area()))
+        .unwrap();
+
+    assert_snapshot!(terminal.backend());
+}
+
+#[cfg(unix)]
+#[test]
+fn classify_renders_type_indicators() {
+    use std::os::unix::fs::{symlink, PermissionsExt};
+
+    // Create a temporary directory with a static name so that test snapshots are consistent
+    let temp_dir = tempfile::Builder::new()
+        .prefix("tiny_fe_classify")
+        .rand_bytes(0)
+        .tempdir()
+        .unwrap();
+
+    let temp_path = temp_dir.path();
+
+    create_dir(temp_path.join("sub_dir")).unwrap();
+    File::create(temp_path.join("file_1.txt")).unwrap();
+    symlink(temp_path.join("file_1.txt"), temp_path.join("link")).unwrap();
+
+    let script = File::create(temp_path.join("script.sh")).unwrap();
+    script
+        .set_permissions(std::fs::Permissions::from_mode(0o755))
+        .unwrap();
+
+    let mut app = App::new(Config {
+        classify: true,
+        ..Default::default()
+    });
+
+    app.change_directory(temp_path).unwrap();
+
+    let mut terminal = Terminal::new(TestBackend::new(80, 10)).unwrap();
+    terminal
+        .draw(|frame| frame.render_widget(&mut app, frame.area()))
+        .unwrap();
+
+    assert_snapshot!(terminal.backend());
+}
+
+fn create_entry(path: &str, kind: EntryKind) -> Entry {
+    let path = PathBuf::from(path);
+    let name = path.file_name().unwrap().to_string_lossy().into_owned();
+
+    Entry {
+        path,
+        kind,
+        name_lower: name.to_lowercase(),
+        name,
+        is_symlink: false,
+        is_executable: false,
+        permissions: None,
+        owner_ids: None,
+    }
+}
+
+fn create_app_from_entries() -> App {
+    App::from_entries(
+        vec![
+            create_entry("/projects/tiny-fe", EntryKind::Directory),
+            create_entry("/projects/website", EntryKind::Directory),
+            create_entry(
+                "/projects/notes.md",
+                EntryKind::File {
+                    extension: Some("md".into()),
+                },
+            ),
+        ],
+        PathBuf::from("/projects"),
+    )
+}
+
+#[test]
+fn search_filters_entries_created_without_file_system() {
+    let mut app = create_app_from_entries();
+
+    app.handle_key_event(KeyCode::Char('/').into(), KeyModifiers::NONE)
+        .unwrap();
+
+    for c in "web".chars() {
+        app.handle_key_event(KeyCode::Char(c).into(), KeyModifiers::NONE)
+            .unwrap();
+    }
+
+    let mut terminal = Terminal::new(TestBackend::new(80, 10)).unwrap();
+    terminal
+        .draw(|frame| frame.render_widget(&mut app, frame.area()))
+        .unwrap();
+
+    assert_snapshot!(terminal.backend());
+}
+
+#[test]
+fn enter_and_exit_returns_selected_entry_created_without_file_system() {
+    let mut app = create_app_from_entries();
+    let mut terminal = Terminal::new(TestBackend::new(80, 10)).unwrap();
+
+    // Select the second entry and go into it while exiting
+    app.handle_key_event(KeyCode::Char('j').into(), KeyModifiers::NONE)
+        .unwrap();
+    app.handle_key_event(KeyCode::Char('j').into(), KeyModifiers::NONE)
+        .unwrap();
+    app.handle_key_event(KeyCode::Char('L').into(), KeyModifiers::SHIFT)
+        .unwrap();
+
+    let result = app.run(&mut terminal).unwrap();
+
+    assert_eq!(
+        result,
+        RunOutcome::ChangedTo(PathBuf::from("/projects/website"))
+    );
+}
+
+#[test]
+fn quit_without_changing_directory_is_cancelled_for_entries_created_without_file_system() {
+    let mut app = create_app_from_entries();
+    let mut terminal = Terminal::new(TestBackend::new(80, 10)).unwrap();
+
+    app.handle_key_event(KeyCode::Char('q').into(), KeyModifiers::CONTROL)
+        .unwrap();
+
+    let result = app.run(&mut terminal).unwrap();
+
+    assert_eq!(result, RunOutcome::Cancelled);
 }